@@ -0,0 +1,111 @@
+use super::*;
+use std::{collections::HashMap, future::Future, rc::Rc};
+use tokio::{sync::Semaphore, task::JoinHandle};
+
+// Drives jobs with async tasks on the current tokio LocalSet
+#[derive(Default)]
+pub struct AsyncScheduler {
+    handles: Vec<(JobId, JoinHandle<()>)>,
+    next_id: u64,
+    // Runs each resource group may have in flight at once
+    group_limits: HashMap<String, Rc<Semaphore>>,
+}
+
+impl AsyncScheduler {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    // Let at most `limit` jobs in `group` run at once. A fire that would go over waits for a
+    // running one to finish
+    pub fn with_group_limit(
+        mut self,
+        group: impl Into<String>,
+        limit: usize,
+    ) -> Result<Self, SchedulerError> {
+        if limit == 0 {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+        self.group_limits
+            .insert(group.into(), Rc::new(Semaphore::new(limit)));
+        Ok(self)
+    }
+
+    // Run the job's task each time it comes due. Schedules aren't Send, so this must be
+    // called from within a tokio LocalSet
+    pub fn spawn<F, Fut>(&mut self, job: Job<F>) -> JobId
+    where
+        F: FnMut() -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        self.spawn_with_context(job.map_task(|mut task| move |_| task()))
+    }
+
+    // As spawn, telling the task which occurrence each run services
+    pub fn spawn_with_context<F, Fut>(&mut self, job: Job<F>) -> JobId
+    where
+        F: FnMut(OccurrenceContext) -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        let id = JobId(self.next_id);
+        self.next_id += 1;
+        let group_limit = job
+            .resource_group()
+            .and_then(|group| self.group_limits.get(group))
+            .cloned();
+
+        let handle = tokio::task::spawn_local(async move {
+            let mut scheduled = ScheduledJob::new(id, job, Utc::now());
+
+            loop {
+                scheduled.refresh(Utc::now());
+                let Some(due) = scheduled.next_due else {
+                    if scheduled.is_finished(Utc::now()) {
+                        break;
+                    }
+                    tokio::time::sleep(IDLE_POLL_INTERVAL).await;
+                    continue;
+                };
+                let wait = (due - Utc::now()).to_std().unwrap_or_default();
+                tokio::time::sleep_until(tokio::time::Instant::now() + wait).await;
+
+                if let Some(context) = scheduled.take_fire(due, Utc::now()) {
+                    // The semaphore is never closed, so acquiring only ever waits
+                    let _permit = match &group_limit {
+                        Some(limit) => limit.acquire().await.ok(),
+                        None => None,
+                    };
+                    (scheduled.job.task)(context).await;
+                }
+            }
+        });
+        self.handles.push((id, handle));
+        id
+    }
+
+    // Cancel one job's driver, returning whether it was running
+    pub fn cancel(&mut self, id: JobId) -> bool {
+        let Some(index) = self.handles.iter().position(|(job_id, _)| *job_id == id) else {
+            return false;
+        };
+        let (_, handle) = self.handles.remove(index);
+        handle.abort();
+        true
+    }
+
+    pub fn stop(&mut self) {
+        for (_, handle) in self.handles.drain(..) {
+            handle.abort();
+        }
+    }
+
+    // Wait until every spawned job has finished
+    pub async fn join(&mut self) {
+        for (_, handle) in self.handles.drain(..) {
+            // An aborted or panicked job just stops being driven
+            let _ = handle.await;
+        }
+    }
+}
@@ -17,4 +17,10 @@ pub enum SchedulerError {
 
     #[error("Invalid date/time specification")]
     InvalidDateTime,
+
+    #[error("Schedule can never produce an occurrence")]
+    Unsatisfiable,
+
+    #[error("Invalid cron {field} field: {value:?}")]
+    InvalidCronField { field: &'static str, value: String },
 }
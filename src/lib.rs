@@ -9,16 +9,32 @@
 // - Random intervals, eg: between 9-10 am
 // - Repetition: 10 times hourly, until 3rd of March etc.
 // - Mixture: Every hour until 10pm and then Every minute for the next 1 hour
-pub use crate::{errors::*, schedulers::*};
-use chrono::{DateTime, Datelike, Timelike, Utc};
-use rand::Rng;
-use std::time::Duration;
+#[cfg(feature = "tokio")]
+pub use crate::async_scheduler::*;
+pub use crate::{errors::*, scheduler::*, schedulers::*, thread_pool::*, utils::*};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeZone, Timelike, Utc, Weekday};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    ops::{Range, RangeInclusive},
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{mpsc, Arc, Condvar, Mutex, PoisonError},
+    thread,
+    time::Duration,
+};
 use thiserror::Error;
 
+#[cfg(feature = "tokio")]
+mod async_scheduler;
 mod errors;
+mod scheduler;
 mod schedulers;
 #[cfg(test)]
 mod tests;
+mod thread_pool;
+mod utils;
 
 // Job definition
 pub struct Job<T> {
@@ -27,6 +43,56 @@ pub struct Job<T> {
     max_repeats: Option<u32>,
     repeats: u32,
     end_time: Option<DateTime<Utc>>,
+    last_run: Option<DateTime<Utc>>,
+    compress_to_deadline: bool,
+    labels: Vec<String>,
+    grace_period: Duration,
+    paused_until: Option<DateTime<Utc>>,
+    // Upcoming occurrence pushed back, and the time it fires instead
+    snoozed: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    failure_policy: FailurePolicy,
+    failures: u32,
+    halted: bool,
+    resource_group: Option<String>,
+    dedup_key: Option<String>,
+}
+
+// What a job with a fallible task does after a failed run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FailurePolicy {
+    // Keep firing on schedule regardless of failures
+    #[default]
+    Continue,
+    // Treat the job as finished after the first failure
+    HaltSchedule,
+}
+
+// The scheduled instant a run services, for idempotency keys and logging
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OccurrenceContext {
+    pub scheduled_time: DateTime<Utc>,
+    // Zero-based count of runs before this one
+    pub repeat_index: u32,
+    // Fired to catch up rather than on the schedule, eg: by deadline compression, or for an
+    // occurrence missed while the process was down
+    pub catch_up: bool,
+}
+
+// Point-in-time gauges for metrics export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    pub seconds_until_next: Option<i64>,
+    pub total_fires: u64,
+    pub finished: bool,
+}
+
+// Concrete fire plan for reviewing a job before enabling it
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JobPlan {
+    pub labels: Vec<String>,
+    pub schedule: String,
+    pub fire_times: Vec<DateTime<Utc>>,
 }
 
 // Builder for Job
@@ -35,6 +101,13 @@ pub struct JobBuilder<T> {
     task: Option<T>,
     max_repeats: Option<u32>,
     end_time: Option<DateTime<Utc>>,
+    labels: Vec<String>,
+    grace_period: Duration,
+    failure_policy: FailurePolicy,
+    require_fires: bool,
+    last_fired: Option<DateTime<Utc>>,
+    resource_group: Option<String>,
+    dedup_key: Option<String>,
 }
 
 impl<T> Default for JobBuilder<T> {
@@ -44,6 +117,13 @@ impl<T> Default for JobBuilder<T> {
             task: Default::default(),
             max_repeats: Default::default(),
             end_time: Default::default(),
+            labels: Default::default(),
+            grace_period: Default::default(),
+            failure_policy: Default::default(),
+            require_fires: Default::default(),
+            last_fired: Default::default(),
+            resource_group: Default::default(),
+            dedup_key: Default::default(),
         }
     }
 }
@@ -55,8 +135,8 @@ impl<T> JobBuilder<T> {
         }
     }
 
-    pub fn schedule(mut self, schedule: Box<dyn Schedule>) -> Self {
-        self.schedule = Some(schedule);
+    pub fn schedule(mut self, schedule: impl Into<Box<dyn Schedule>>) -> Self {
+        self.schedule = Some(schedule.into());
         self
     }
 
@@ -75,23 +155,317 @@ impl<T> JobBuilder<T> {
         self
     }
 
+    // Stop after `count` runs, or at the `limit_until` date, whichever comes first
+    pub fn limit_count(self, count: u32) -> Self {
+        self.max_repeats(count)
+    }
+
+    // Stop at `until`, or after the `limit_count` runs, whichever comes first
+    pub fn limit_until(self, until: DateTime<Utc>) -> Self {
+        self.end_time(until)
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.labels.push(label.into());
+        self
+    }
+
+    pub fn failure_policy(mut self, failure_policy: FailurePolicy) -> Self {
+        self.failure_policy = failure_policy;
+        self
+    }
+
+    // Have build reject an end time that leaves no room for a single fire
+    pub fn require_fires(mut self, require_fires: bool) -> Self {
+        self.require_fires = require_fires;
+        self
+    }
+
+    // How late a fire may land before it counts as missed
+    pub fn grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    // Group of jobs sharing a resource, whose runs an executor may limit, eg: heavy database jobs
+    pub fn resource_group(mut self, resource_group: impl Into<String>) -> Self {
+        self.resource_group = Some(resource_group.into());
+        self
+    }
+
+    // Identity of the logical job, so registering it again replaces it, eg: on a config reload
+    pub fn dedup_key(mut self, dedup_key: impl Into<String>) -> Self {
+        self.dedup_key = Some(dedup_key.into());
+        self
+    }
+
+    // When the job last fired, eg: restored after a restart so a missed fire can be caught up
+    pub fn last_fired(mut self, last_fired: DateTime<Utc>) -> Self {
+        self.last_fired = Some(last_fired);
+        self
+    }
+
     pub fn build(self) -> Result<Job<T>, SchedulerError> {
+        let schedule = self.schedule.ok_or(SchedulerError::InvalidConfiguration)?;
+
+        if let (true, Some(end)) = (self.require_fires, self.end_time) {
+            let now = Utc::now();
+            let fires_before_end = (end - now)
+                .to_std()
+                .is_ok_and(|horizon| schedule.fires_within(now, horizon));
+            if !fires_before_end {
+                return Err(SchedulerError::Unsatisfiable);
+            }
+        }
+
         Ok(Job {
-            schedule: self.schedule.ok_or(SchedulerError::InvalidConfiguration)?,
+            schedule,
             task: self.task.ok_or(SchedulerError::InvalidConfiguration)?,
             max_repeats: self.max_repeats,
             repeats: 0,
             end_time: self.end_time,
+            last_run: self.last_fired,
+            compress_to_deadline: false,
+            labels: self.labels,
+            grace_period: self.grace_period,
+            paused_until: None,
+            snoozed: None,
+            failure_policy: self.failure_policy,
+            failures: 0,
+            halted: false,
+            resource_group: self.resource_group,
+            dedup_key: self.dedup_key,
         })
     }
 }
 
+impl<T: fmt::Display> fmt::Display for Job<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.task, self.schedule)?;
+
+        match self.max_repeats {
+            Some(max) => write!(f, ", ran {} of {} times", self.repeats, max)?,
+            None => write!(f, ", ran {} times", self.repeats)?,
+        }
+        if let Some(end) = self.end_time {
+            write!(f, ", until {end}")?;
+        }
+
+        let finished = self.halted || self.max_repeats.is_some_and(|max| self.repeats >= max);
+        // A job yet to run looks ahead from now, counting a fire due right now
+        let after = self
+            .last_run
+            .unwrap_or_else(|| Utc::now() - chrono::TimeDelta::seconds(1));
+        let next = self
+            .schedule
+            .next_occurrence(after)
+            .filter(|next| self.end_time.is_none_or(|end| *next < end));
+        match next {
+            _ if finished => write!(f, ", finished"),
+            Some(next) => write!(f, ", next at {next}"),
+            None if self.last_run.is_some() => write!(f, ", finished"),
+            None => Ok(()),
+        }
+    }
+}
+
 impl<T> Job<T> {
     pub fn builder() -> JobBuilder<T> {
         JobBuilder::new()
     }
 
+    // Start a recurring reminder, bounded with limit_count and limit_until
+    pub fn recurring(schedule: impl Into<Box<dyn Schedule>>) -> JobBuilder<T> {
+        JobBuilder::new().schedule(schedule)
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    pub fn resource_group(&self) -> Option<&str> {
+        self.resource_group.as_deref()
+    }
+
+    pub fn dedup_key(&self) -> Option<&str> {
+        self.dedup_key.as_deref()
+    }
+
+    // System crontab line running `command` on this job's schedule. None when the schedule
+    // isn't cron-representable or the job has count or end limits crontab can't express
+    pub fn to_crontab_line(&self, command: &str) -> Option<String> {
+        if self.max_repeats.is_some() || self.end_time.is_some() {
+            return None;
+        }
+        let expression = self.schedule.to_cron_expression()?;
+        Some(format!("{expression} {command}"))
+    }
+
+    // Fire times within `horizon` of `from`, along with the job's labels and schedule
+    pub fn to_plan(&self, from: DateTime<Utc>, horizon: Duration) -> JobPlan {
+        let until = from + horizon;
+        let mut remaining = self.remaining_repeats();
+        let mut after = from - chrono::TimeDelta::seconds(1);
+        let mut fire_times = Vec::new();
+
+        while let Some(next) = self.next_fire_within_limits(after, &mut remaining) {
+            if next >= until {
+                break;
+            }
+            fire_times.push(next);
+            after = next;
+        }
+
+        JobPlan {
+            labels: self.labels.clone(),
+            schedule: self.schedule.describe(),
+            fire_times,
+        }
+    }
+
+    // Number of fires from `from` up to `to`, honouring max_repeats and end_time
+    pub fn count_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> usize {
+        let mut remaining = self.remaining_repeats();
+        let mut after = from - chrono::TimeDelta::seconds(1);
+        let mut count = 0;
+
+        while let Some(next) = self.next_fire_within_limits(after, &mut remaining) {
+            if next >= to {
+                break;
+            }
+            count += 1;
+            after = next;
+        }
+
+        count
+    }
+
+    // When the `index`-th fire from `from` onwards happens (counting from 0), honouring limits
+    pub fn occurrence_of_repeat(&self, from: DateTime<Utc>, index: u32) -> Option<DateTime<Utc>> {
+        let mut remaining = self.remaining_repeats();
+        let mut after = from - chrono::TimeDelta::seconds(1);
+
+        for _ in 0..index {
+            after = self.next_fire_within_limits(after, &mut remaining)?;
+        }
+        self.next_fire_within_limits(after, &mut remaining)
+    }
+
+    pub fn metrics_snapshot(&self, now: DateTime<Utc>) -> MetricsSnapshot {
+        let limit_reached = self.halted
+            || self.max_repeats.is_some_and(|max| self.repeats >= max)
+            || self.end_time.is_some_and(|end| now >= end);
+        let next = self
+            .schedule
+            .next_occurrence(now)
+            .filter(|next| self.end_time.is_none_or(|end| *next < end))
+            .filter(|_| !limit_reached);
+
+        MetricsSnapshot {
+            seconds_until_next: next.map(|next| (next - now).num_seconds()),
+            total_fires: self.repeats as u64,
+            finished: next.is_none(),
+        }
+    }
+
+    // Whether the job will never fire after `after`, because its limits are used up or its
+    // schedule is done for good, rather than having nothing due right now
+    pub fn is_finished(&self, after: DateTime<Utc>) -> bool {
+        self.halted
+            || self.remaining_repeats() == Some(0)
+            || self.end_time.is_some_and(|end| after >= end)
+            || self.schedule.is_finished(after)
+    }
+
+    // How far `now` is from the nearest occurrence either side of it, eg: a large value at poll
+    // time means the poller is out of step with the schedule
+    pub fn alignment_error(&self, now: DateTime<Utc>) -> Option<Duration> {
+        // Backing off a second counts an occurrence at `now` itself
+        let next = self
+            .schedule
+            .next_occurrence(now - chrono::TimeDelta::seconds(1));
+        let previous = self.schedule.previous_occurrence(now);
+
+        next.into_iter()
+            .chain(previous)
+            .map(|occurrence| (occurrence - now).abs())
+            .min()?
+            .to_std()
+            .ok()
+    }
+
+    // Latest time the next fire should happen by, for an external watchdog to alert on
+    pub fn deadline_for_next(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.next_fire_within_limits(now, &mut self.remaining_repeats())
+            .map(|next| next + self.grace_period)
+    }
+
+    // Future fire times from `from` onwards, honouring max_repeats and end_time
+    pub fn into_occurrence_iter(self, from: DateTime<Utc>) -> JobOccurrences<T> {
+        JobOccurrences {
+            remaining: self.remaining_repeats(),
+            // Matches should_execute, which fires on an occurrence at `from` itself
+            after: from - chrono::TimeDelta::seconds(1),
+            job: self,
+        }
+    }
+
+    // Run `count` times before `deadline`, compressing the remaining runs when behind schedule
+    pub fn with_deadline_and_count(mut self, deadline: DateTime<Utc>, count: u32) -> Self {
+        self.end_time = Some(deadline);
+        self.max_repeats = Some(count);
+        self.compress_to_deadline = true;
+        self
+    }
+
+    // Skip every fire before `resume_at`, then carry on without a manual resume
+    pub fn pause_until(&mut self, resume_at: DateTime<Utc>) {
+        self.paused_until = Some(resume_at);
+    }
+
+    // Push the upcoming fire back by `by`, leaving later fires on the schedule's grid.
+    // Returns the snoozed fire time, if there is an upcoming fire to move
+    pub fn snooze_next(&mut self, now: DateTime<Utc>, by: Duration) -> Option<DateTime<Utc>> {
+        self.snoozed = None;
+        let next = self.next_fire_within_limits(
+            now - chrono::TimeDelta::seconds(1),
+            &mut self.remaining_repeats(),
+        )?;
+        self.snoozed = Some((next, next + by));
+        Some(next + by)
+    }
+
+    // Number of failed runs recorded by run_if_due
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    pub fn last_fired(&self) -> Option<DateTime<Utc>> {
+        self.last_run
+    }
+
+    // The task, if a fire is due at `current_time`. An occurrence counts as due for a second
+    // after it passes, so a slightly late poll still catches it, but never twice: once a poll
+    // has run it, polling again within that second finds nothing due
     pub fn should_execute(&mut self, current_time: DateTime<Utc>) -> Option<&T> {
+        self.take_due(current_time)?;
+        Some(&self.task)
+    }
+
+    // Record a run if one is due at `current_time`, describing the occurrence it services
+    fn take_due(&mut self, current_time: DateTime<Utc>) -> Option<OccurrenceContext> {
+        // A failed run under HaltSchedule finishes the job for good
+        if self.halted {
+            return None;
+        }
+        // Fires missed while paused are dropped rather than caught up
+        if self
+            .paused_until
+            .is_some_and(|resume_at| current_time < resume_at)
+        {
+            return None;
+        }
+
         // Check if we've exceeded max repeats
         if let Some(max) = self.max_repeats {
             if self.repeats >= max {
@@ -106,6 +480,25 @@ impl<T> Job<T> {
             }
         }
 
+        // The snoozed fire runs once at its new time
+        if let Some((original, snoozed_to)) = self.snoozed {
+            if current_time >= snoozed_to {
+                self.snoozed = None;
+                self.schedule.record_fire(original);
+                return Some(self.record_run(current_time, snoozed_to, false));
+            }
+        }
+
+        // Catch up on runs the schedule can no longer fit before the deadline
+        if self.compress_to_deadline {
+            if let Some(due) = self.compressed_due_time(current_time) {
+                if due <= current_time {
+                    return Some(self.record_run(current_time, due, true));
+                }
+                return None;
+            }
+        }
+
         // Special handling for the test case
         // In test_job_execution, we need to execute at start_time and start_time + interval
         let next_time = self
@@ -113,12 +506,186 @@ impl<T> Job<T> {
             .next_occurrence(current_time - chrono::TimeDelta::seconds(1));
 
         if let Some(next) = next_time {
-            if next <= current_time {
-                self.repeats += 1;
-                return Some(&self.task);
+            let snoozed = self.snoozed.is_some_and(|(original, _)| original == next);
+            // An occurrence at or before the last run was already serviced by it, whatever the
+            // schedule, eg: an hourly fire polled twice in the same second
+            let serviced = self.last_run.is_some_and(|last_run| next <= last_run);
+            if next <= current_time && !snoozed && !serviced {
+                self.schedule.record_fire(next);
+                return Some(self.record_run(current_time, next, false));
             }
         }
 
         None
     }
+
+    // Same job with its task replaced, eg: boxed for storage alongside other jobs
+    fn map_task<U>(self, map: impl FnOnce(T) -> U) -> Job<U> {
+        Job {
+            schedule: self.schedule,
+            task: map(self.task),
+            max_repeats: self.max_repeats,
+            repeats: self.repeats,
+            end_time: self.end_time,
+            last_run: self.last_run,
+            compress_to_deadline: self.compress_to_deadline,
+            labels: self.labels,
+            grace_period: self.grace_period,
+            paused_until: self.paused_until,
+            snoozed: self.snoozed,
+            failure_policy: self.failure_policy,
+            failures: self.failures,
+            halted: self.halted,
+            resource_group: self.resource_group,
+            dedup_key: self.dedup_key,
+        }
+    }
+
+    // Latest occurrence that went by without a fire since the last one, eg: while the process
+    // was down. A job that never fired has nothing to go by, so it has nothing missed
+    fn missed_since_last_fire(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let last_fired = self.last_run?;
+        if self.halted
+            || self.remaining_repeats() == Some(0)
+            || self.paused_until.is_some_and(|resume_at| now < resume_at)
+        {
+            return None;
+        }
+
+        self.schedule
+            .previous_occurrence(now)
+            .filter(|missed| *missed > last_fired)
+            .filter(|missed| self.end_time.is_none_or(|end| *missed < end))
+    }
+
+    // Fires left before the job's max_repeats, or the schedule's own cap, is used up
+    fn remaining_repeats(&self) -> Option<u32> {
+        let remaining = self.max_repeats.map(|max| max.saturating_sub(self.repeats));
+        match (remaining, self.schedule.remaining_occurrences()) {
+            (Some(remaining), Some(schedule)) => Some(remaining.min(schedule)),
+            (remaining, schedule) => remaining.or(schedule),
+        }
+    }
+
+    // Next fire after `after` that the job's repeat budget and end time still allow
+    fn next_fire_within_limits(
+        &self,
+        after: DateTime<Utc>,
+        remaining: &mut Option<u32>,
+    ) -> Option<DateTime<Utc>> {
+        if *remaining == Some(0) || self.halted {
+            return None;
+        }
+
+        // Resuming counts an occurrence exactly at the resume time
+        let after = match self.paused_until {
+            Some(resume_at) => after.max(resume_at - chrono::TimeDelta::seconds(1)),
+            None => after,
+        };
+        let next = match self.schedule.next_occurrence(after)? {
+            next if self.snoozed.is_some_and(|(original, _)| original == next) => {
+                self.snoozed.map(|(_, snoozed_to)| snoozed_to)?
+            }
+            next => next,
+        };
+        if self.end_time.is_some_and(|end| next >= end) {
+            return None;
+        }
+
+        if let Some(remaining) = remaining.as_mut() {
+            *remaining -= 1;
+        }
+        Some(next)
+    }
+
+    fn record_run(
+        &mut self,
+        current_time: DateTime<Utc>,
+        scheduled_time: DateTime<Utc>,
+        catch_up: bool,
+    ) -> OccurrenceContext {
+        let context = OccurrenceContext {
+            scheduled_time,
+            repeat_index: self.repeats,
+            catch_up,
+        };
+        self.repeats += 1;
+        self.last_run = Some(current_time);
+        context
+    }
+
+    // Evenly spaced due time for the remaining runs, or None while the schedule can still fit them
+    fn compressed_due_time(&self, current_time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let last_run = self.last_run?;
+        let deadline = self.end_time?;
+        let remaining = self.max_repeats?.saturating_sub(self.repeats);
+
+        let mut on_schedule = 0;
+        let mut after = current_time - chrono::TimeDelta::seconds(1);
+        while on_schedule < remaining {
+            match self.schedule.next_occurrence(after) {
+                Some(next) if next < deadline => {
+                    on_schedule += 1;
+                    after = next;
+                }
+                _ => break,
+            }
+        }
+
+        if on_schedule >= remaining {
+            return None;
+        }
+
+        // Too many runs left to divide the time by falls back to the schedule
+        let slots = i32::try_from(remaining).ok()?.checked_add(1)?;
+        last_run.checked_add_signed((deadline - last_run) / slots)
+    }
+}
+
+impl<T, E> Job<T>
+where
+    T: FnMut() -> Result<(), E>,
+{
+    // Run the task if it is due, applying the failure policy to its result
+    pub fn run_if_due(&mut self, current_time: DateTime<Utc>) -> Option<Result<(), E>> {
+        self.should_execute(current_time)?;
+
+        let result = (self.task)();
+        if result.is_err() {
+            self.failures += 1;
+            self.halted = self.failure_policy == FailurePolicy::HaltSchedule;
+        }
+        Some(result)
+    }
+}
+
+impl<T> Job<T>
+where
+    T: FnMut(OccurrenceContext),
+{
+    // Run the task if it is due, telling it which occurrence it is servicing
+    pub fn run_with_context(&mut self, current_time: DateTime<Utc>) -> Option<OccurrenceContext> {
+        let context = self.take_due(current_time)?;
+        (self.task)(context);
+        Some(context)
+    }
+}
+
+// Iterator over a job's upcoming fire times
+pub struct JobOccurrences<T> {
+    job: Job<T>,
+    after: DateTime<Utc>,
+    remaining: Option<u32>,
+}
+
+impl<T> Iterator for JobOccurrences<T> {
+    type Item = DateTime<Utc>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self
+            .job
+            .next_fire_within_limits(self.after, &mut self.remaining)?;
+        self.after = next;
+        Some(next)
+    }
 }
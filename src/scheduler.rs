@@ -0,0 +1,445 @@
+use super::*;
+
+// Identifier handed out when a job is registered with a Scheduler
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JobId(pub(crate) u64);
+
+// How often jobs with nothing due are asked again, eg: a gated schedule whose flag is off
+pub(crate) const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Upcoming occurrences compared when looking for duplicate jobs
+const DUPLICATE_SAMPLES: usize = 16;
+
+// Registered job along with the fire it is waiting for. Both Scheduler and AsyncScheduler
+// drive their jobs through it, so they agree on when a fire is due
+pub(crate) struct ScheduledJob<F> {
+    id: JobId,
+    pub(crate) job: Job<F>,
+    pub(crate) next_due: Option<DateTime<Utc>>,
+    // Last fire handled, so asking again never hands it back
+    last_due: Option<DateTime<Utc>>,
+}
+
+impl<F> ScheduledJob<F> {
+    pub(crate) fn new(id: JobId, job: Job<F>, now: DateTime<Utc>) -> Self {
+        let mut scheduled = Self {
+            id,
+            job,
+            next_due: None,
+            last_due: None,
+        };
+        scheduled.refresh(now);
+        scheduled
+    }
+
+    // Matches should_execute, which fires on an occurrence at the current time itself
+    fn poll_after(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let after = now - chrono::TimeDelta::seconds(1);
+        self.last_due.map_or(after, |last_due| last_due.max(after))
+    }
+
+    // Ask the schedule again when it had nothing to report, as it may have something now
+    pub(crate) fn refresh(&mut self, now: DateTime<Utc>) {
+        if self.next_due.is_none() {
+            let after = self.poll_after(now);
+            self.next_due = self
+                .job
+                .next_fire_within_limits(after, &mut self.job.remaining_repeats());
+        }
+    }
+
+    pub(crate) fn is_finished(&self, now: DateTime<Utc>) -> bool {
+        self.next_due.is_none() && self.job.is_finished(self.poll_after(now))
+    }
+
+    // Record the fire due at `due`, returning the occurrence if it still counted, and line up
+    // the next
+    pub(crate) fn take_fire(
+        &mut self,
+        due: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Option<OccurrenceContext> {
+        // Judge the fire at its own due time, so a late wake-up still runs it once
+        let fired = self.job.take_due(due);
+
+        // Fires missed while running late are skipped rather than replayed
+        self.last_due = Some(due);
+        let job = &self.job;
+        self.next_due = job.next_fire_within_limits(due.max(now), &mut job.remaining_repeats());
+        fired
+    }
+
+    // Latest the upcoming fire may be held back to, waiting for other jobs to go out with it
+    fn fire_by(&self) -> Option<DateTime<Utc>> {
+        let slack = chrono::TimeDelta::from_std(self.job.schedule.slack()).unwrap_or_default();
+        self.next_due.map(|due| due + slack)
+    }
+}
+
+// Stops a running Scheduler from another thread
+#[derive(Clone, Default)]
+pub struct StopHandle {
+    stopped: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl StopHandle {
+    pub fn stop(&self) {
+        let (stopped, wake) = &*self.stopped;
+        *stopped.lock().unwrap() = true;
+        wake.notify_all();
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        *self.stopped.0.lock().unwrap()
+    }
+
+    // Sleep for `timeout`, returning early (true) once stopped
+    fn wait(&self, timeout: Duration) -> bool {
+        let (stopped, wake) = &*self.stopped;
+        let guard = stopped.lock().unwrap();
+        let (guard, _) = wake
+            .wait_timeout_while(guard, timeout, |stopped| !*stopped)
+            .unwrap();
+        *guard
+    }
+}
+
+// Task of a job registered with a Scheduler, told which occurrence each run services
+type SchedulerTask = Box<dyn FnMut(OccurrenceContext)>;
+
+// Hook handed the jobs of a batch and the time the batch opened
+type BatchHook = Box<dyn FnMut(Vec<JobId>, DateTime<Utc>)>;
+
+// Owns many jobs and runs each task when its schedule comes due
+#[derive(Default)]
+pub struct Scheduler {
+    jobs: Vec<ScheduledJob<SchedulerTask>>,
+    next_id: u64,
+    stop: StopHandle,
+    fire_overdue_on_start: bool,
+    batching: Option<(chrono::TimeDelta, BatchHook)>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    // Have start fire each job once straight away if it missed an occurrence since it last
+    // fired, before carrying on with its schedule
+    pub fn with_fire_overdue_on_start(mut self, fire_overdue_on_start: bool) -> Self {
+        self.fire_overdue_on_start = fire_overdue_on_start;
+        self
+    }
+
+    // Collect the jobs coming due within `window` of the first one and hand them to `on_batch`
+    // in one call, instead of running their own tasks
+    pub fn with_batching(
+        mut self,
+        window: Duration,
+        on_batch: impl FnMut(Vec<JobId>, DateTime<Utc>) + 'static,
+    ) -> Result<Self, SchedulerError> {
+        let window =
+            chrono::TimeDelta::from_std(window).map_err(|_| SchedulerError::InvalidDuration)?;
+        self.batching = Some((window, Box::new(on_batch)));
+        Ok(self)
+    }
+
+    // Register a job, returning its id. A job with the dedup key of one already registered
+    // replaces it and takes over its id
+    pub fn add_job<F: FnMut() + 'static>(&mut self, job: Job<F>) -> JobId {
+        self.add_job_with_context(job.map_task(|mut task| move |_| task()))
+    }
+
+    // Register a job whose task is told which occurrence each run services, catch-ups included
+    pub fn add_job_with_context<F: FnMut(OccurrenceContext) + 'static>(
+        &mut self,
+        job: Job<F>,
+    ) -> JobId {
+        let existing = job.dedup_key().and_then(|key| {
+            self.jobs
+                .iter()
+                .position(|scheduled| scheduled.job.dedup_key() == Some(key))
+        });
+        let id = match existing {
+            Some(index) => self.jobs[index].id,
+            None => {
+                self.next_id += 1;
+                JobId(self.next_id - 1)
+            }
+        };
+
+        let job = job.map_task(|task| Box::new(task) as SchedulerTask);
+        let scheduled = ScheduledJob::new(id, job, Utc::now());
+        match existing {
+            Some(index) => self.jobs[index] = scheduled,
+            None => self.jobs.push(scheduled),
+        }
+        id
+    }
+
+    // Register a job whose task runs on `pool`, so a slow run doesn't hold up other jobs
+    pub fn add_pooled_job<F: FnMut() + Send + 'static>(
+        &mut self,
+        job: Job<F>,
+        pool: &ThreadPoolExecutor,
+    ) -> JobId {
+        let job = job.map_task(|task| pool.dispatcher(task));
+        self.add_job(job)
+    }
+
+    pub fn remove_job(&mut self, id: JobId) -> bool {
+        let before = self.jobs.len();
+        self.jobs.retain(|scheduled| scheduled.id != id);
+        self.jobs.len() < before
+    }
+
+    pub fn job_ids(&self) -> Vec<JobId> {
+        self.jobs.iter().map(|scheduled| scheduled.id).collect()
+    }
+
+    // Each job's next fire within `window` of `now`, soonest first, eg: for a "coming up" list
+    pub fn upcoming_within(
+        &self,
+        now: DateTime<Utc>,
+        window: Duration,
+    ) -> Vec<(JobId, DateTime<Utc>)> {
+        let until = now + window;
+        let mut upcoming: Vec<(JobId, DateTime<Utc>)> = self
+            .jobs
+            .iter()
+            .filter_map(|scheduled| {
+                let job = &scheduled.job;
+                let next = job.next_fire_within_limits(
+                    scheduled.poll_after(now),
+                    &mut job.remaining_repeats(),
+                )?;
+
+                (next <= until).then_some((scheduled.id, next))
+            })
+            .collect();
+        upcoming.sort_by_key(|&(id, next)| (next, id));
+        upcoming
+    }
+
+    // Total fires across all jobs within `window`, eg: for capacity planning
+    pub fn aggregate_fire_count(&self, window: Range<DateTime<Utc>>) -> usize {
+        self.jobs
+            .iter()
+            .map(|scheduled| scheduled.job.count_between(window.start, window.end))
+            .sum()
+    }
+
+    // Pairs of jobs with the same labels whose schedules fire at the same times, eg: a job
+    // registered twice by mistake
+    pub fn find_duplicates(&self) -> Vec<(JobId, JobId)> {
+        let now = Utc::now();
+        let mut duplicates = Vec::new();
+
+        for (index, first) in self.jobs.iter().enumerate() {
+            for second in &self.jobs[index + 1..] {
+                if first.job.labels == second.job.labels
+                    && schedule_eq(
+                        &*first.job.schedule,
+                        &*second.job.schedule,
+                        now,
+                        DUPLICATE_SAMPLES,
+                    )
+                {
+                    duplicates.push((first.id, second.id));
+                }
+            }
+        }
+
+        duplicates
+    }
+
+    // Run every job whose fire is due by `now`, returning the ids that ran. A lone due job with
+    // slack is held back until another job comes due or its slack runs out
+    pub fn run_pending(&mut self, now: DateTime<Utc>) -> Vec<JobId> {
+        let mut ran = Vec::new();
+
+        for scheduled in &mut self.jobs {
+            scheduled.refresh(now);
+        }
+        if self.batching.is_some() {
+            return self.run_batch(now);
+        }
+        let due = self
+            .jobs
+            .iter()
+            .filter(|scheduled| scheduled.next_due.is_some_and(|due| due <= now))
+            .count();
+        let out_of_slack = self
+            .jobs
+            .iter()
+            .any(|scheduled| scheduled.fire_by().is_some_and(|fire_by| fire_by <= now));
+        if due < 2 && !out_of_slack {
+            return ran;
+        }
+
+        for scheduled in &mut self.jobs {
+            let Some(due) = scheduled.next_due.filter(|due| *due <= now) else {
+                continue;
+            };
+            if let Some(context) = scheduled.take_fire(due, now) {
+                (scheduled.job.task)(context);
+                ran.push(scheduled.id);
+            }
+        }
+
+        ran
+    }
+
+    // Hand every job due within the batch window of the earliest one to the batch hook, once
+    // that window has gone by
+    fn run_batch(&mut self, now: DateTime<Utc>) -> Vec<JobId> {
+        let Some((window, on_batch)) = &mut self.batching else {
+            return Vec::new();
+        };
+        let Some(opened) = self
+            .jobs
+            .iter()
+            .filter_map(|scheduled| scheduled.next_due)
+            .min()
+        else {
+            return Vec::new();
+        };
+        let closes = opened + *window;
+        if now < closes {
+            return Vec::new();
+        }
+
+        let mut batch = Vec::new();
+        for scheduled in &mut self.jobs {
+            let Some(due) = scheduled.next_due.filter(|due| *due <= closes) else {
+                continue;
+            };
+            if scheduled.take_fire(due, now).is_some() {
+                batch.push(scheduled.id);
+            }
+        }
+        if !batch.is_empty() {
+            on_batch(batch.clone(), opened);
+        }
+        batch
+    }
+
+    // Run every job's task for each of its fires from `from` up to `to`, in time order across
+    // jobs and without waiting on the clock, eg: to reprocess historical data. Tasks added with
+    // add_job_with_context are told the historical instant each run stands in for. Jobs keep
+    // their live state, so their regular fires carry on as before. Returns each fire run
+    pub fn backfill(
+        &mut self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Vec<(JobId, DateTime<Utc>)> {
+        let horizon = (to - from).to_std().unwrap_or_default();
+        let mut fires: Vec<(DateTime<Utc>, usize, u32)> = self
+            .jobs
+            .iter()
+            .enumerate()
+            .flat_map(|(index, scheduled)| {
+                let plan = scheduled.job.to_plan(from, horizon);
+                let repeats = scheduled.job.repeats;
+                (repeats..)
+                    .zip(plan.fire_times)
+                    .map(move |(repeat_index, time)| (time, index, repeat_index))
+            })
+            .collect();
+        // Jobs firing at the same time run in the order they were added
+        fires.sort();
+
+        fires
+            .into_iter()
+            .map(|(time, index, repeat_index)| {
+                let scheduled = &mut self.jobs[index];
+                (scheduled.job.task)(OccurrenceContext {
+                    scheduled_time: time,
+                    repeat_index,
+                    catch_up: false,
+                });
+                (scheduled.id, time)
+            })
+            .collect()
+    }
+
+    // Catch up once on the latest fire each job missed, however many went by
+    fn fire_overdue(&mut self, now: DateTime<Utc>) {
+        for scheduled in &mut self.jobs {
+            let Some(missed) = scheduled.job.missed_since_last_fire(now) else {
+                continue;
+            };
+            scheduled.job.schedule.record_fire(missed);
+            let context = scheduled.job.record_run(now, missed, true);
+            (scheduled.job.task)(context);
+
+            // The catch-up may have used the last repeat, so ask again from the missed fire
+            scheduled.last_due = Some(missed);
+            scheduled.next_due = None;
+            scheduled.refresh(now);
+        }
+    }
+
+    // Earliest known time a job has to fire by, counting any slack it has. None when no job has
+    // a fire lined up, which doesn't mean they're all finished: see is_finished
+    pub fn next_wake(&self) -> Option<DateTime<Utc>> {
+        // A batch goes out once its window has closed
+        if let Some((window, _)) = &self.batching {
+            let opened = self
+                .jobs
+                .iter()
+                .filter_map(|scheduled| scheduled.next_due)
+                .min();
+            return opened.map(|opened| opened + *window);
+        }
+        self.jobs
+            .iter()
+            .filter_map(|scheduled| scheduled.fire_by())
+            .min()
+    }
+
+    // Whether every job is done for good, so there's nothing left to wait for
+    pub fn is_finished(&self, now: DateTime<Utc>) -> bool {
+        self.jobs.iter().all(|scheduled| scheduled.is_finished(now))
+    }
+
+    pub fn stop_handle(&self) -> StopHandle {
+        self.stop.clone()
+    }
+
+    pub fn stop(&self) {
+        self.stop.stop();
+    }
+
+    // Block the current thread running due tasks, until stopped or every job is finished
+    pub fn start(&mut self) {
+        if self.fire_overdue_on_start {
+            self.fire_overdue(Utc::now());
+        }
+
+        while !self.stop.is_stopped() {
+            let now = Utc::now();
+            self.run_pending(now);
+            if self.is_finished(now) {
+                break;
+            }
+
+            // Jobs with nothing lined up are asked again shortly
+            let idle = self
+                .jobs
+                .iter()
+                .any(|scheduled| scheduled.next_due.is_none());
+            let poll_at = idle.then(|| now + IDLE_POLL_INTERVAL);
+            let Some(wake_at) = self.next_wake().into_iter().chain(poll_at).min() else {
+                break;
+            };
+            let sleep = (wake_at - Utc::now()).to_std().unwrap_or_default();
+            if self.stop.wait(sleep) {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use super::*;
+
+// Schedule firing more often as a target time nears, eg: reminders before a deadline
+pub struct ApproachingSchedule {
+    target: DateTime<Utc>,
+    far_interval: Duration,
+    near_interval: Duration,
+    near_window: Duration,
+}
+
+impl ApproachingSchedule {
+    pub fn new(
+        target: DateTime<Utc>,
+        far_interval: Duration,
+        near_interval: Duration,
+        near_window: Duration,
+    ) -> Result<Self, SchedulerError> {
+        if far_interval.is_zero() || near_interval.is_zero() {
+            return Err(SchedulerError::InvalidDuration);
+        }
+        if near_interval > far_interval {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+
+        Ok(Self {
+            target,
+            far_interval,
+            near_interval,
+            near_window,
+        })
+    }
+}
+
+// Latest point of the grid counting back from `anchor` by `step` that is still after `after`,
+// or None when it lies further back than can be represented
+fn grid_point_after(
+    anchor: DateTime<Utc>,
+    step: Duration,
+    after: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let until_anchor_ms = (anchor - after).num_milliseconds();
+    let step_ms = i64::try_from(step.as_millis()).ok()?;
+    let steps_back = (until_anchor_ms - 1).checked_div(step_ms)?;
+    let back = chrono::TimeDelta::try_milliseconds(steps_back.checked_mul(step_ms)?)?;
+    anchor.checked_sub_signed(back)
+}
+
+impl Schedule for ApproachingSchedule {
+    fn describe(&self) -> String {
+        format!(
+            "every {} until {} before {}, then every {}",
+            format_duration(self.far_interval),
+            format_duration(self.near_window),
+            self.target,
+            format_duration(self.near_interval)
+        )
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        // Both grids count back from fixed points, so the cadence never drifts
+        let near_start = self.target - self.near_window;
+        if after < near_start {
+            grid_point_after(near_start, self.far_interval, after)
+        } else if after < self.target {
+            grid_point_after(self.target, self.near_interval, after)
+        } else {
+            None
+        }
+    }
+}
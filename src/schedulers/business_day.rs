@@ -0,0 +1,82 @@
+use super::*;
+
+// Dates a BusinessDaySchedule skips on top of weekends, eg: public holidays
+pub trait HolidayCalendar {
+    fn is_holiday(&self, date: NaiveDate) -> bool;
+}
+
+// A fixed list of holiday dates
+impl HolidayCalendar for Vec<NaiveDate> {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+// Schedule firing at a fixed time on every Monday to Friday that isn't a holiday
+pub struct BusinessDaySchedule {
+    time: NaiveTime,
+    holidays: Option<Box<dyn HolidayCalendar>>,
+    // Shared working days, replacing the Monday to Friday default
+    calendar: Option<Arc<Calendar>>,
+}
+
+impl BusinessDaySchedule {
+    pub fn new(time: NaiveTime) -> Self {
+        Self {
+            time,
+            holidays: None,
+            calendar: None,
+        }
+    }
+
+    pub fn with_holidays(mut self, holidays: impl HolidayCalendar + 'static) -> Self {
+        self.holidays = Some(Box::new(holidays));
+        self
+    }
+
+    // Follow a calendar shared with other schedules, eg: one per exchange or country
+    pub fn with_calendar(mut self, calendar: Arc<Calendar>) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
+
+    fn is_business_day(&self, date: NaiveDate) -> bool {
+        let working_day = match &self.calendar {
+            Some(calendar) => calendar.is_business_day(date),
+            None => !is_weekend(date),
+        };
+        working_day
+            && self
+                .holidays
+                .as_ref()
+                .is_none_or(|holidays| !holidays.is_holiday(date))
+    }
+}
+
+impl Schedule for BusinessDaySchedule {
+    fn describe(&self) -> String {
+        let calendar = self
+            .calendar
+            .as_ref()
+            .map(|calendar| format!(" on the {} calendar", calendar.name()))
+            .unwrap_or_default();
+        format!(
+            "every business day{calendar} at {}",
+            self.time.format("%H:%M")
+        )
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut date = after.date_naive();
+
+        // A calendar marking a decade of days as holidays has nothing left to fire on
+        for _ in 0..3660 {
+            let candidate = date.and_time(self.time).and_utc();
+            if candidate > after && self.is_business_day(date) {
+                return Some(candidate);
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
@@ -0,0 +1,201 @@
+use super::*;
+
+// Interval schedule that only advances during business hours
+pub struct BusinessHoursIntervalSchedule {
+    interval: Duration,
+    start_time: DateTime<Utc>,
+    day_start: NaiveTime,
+    day_end: NaiveTime,
+    skip_weekends: bool,
+    max_occurrences: Option<u32>,
+}
+
+impl BusinessHoursIntervalSchedule {
+    pub fn new(
+        interval: Duration,
+        start_time: DateTime<Utc>,
+        day_start: NaiveTime,
+        day_end: NaiveTime,
+    ) -> Result<Self, SchedulerError> {
+        if interval.as_secs() == 0 {
+            return Err(SchedulerError::InvalidDuration);
+        }
+        if day_start >= day_end {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+
+        Ok(Self {
+            interval,
+            start_time,
+            day_start,
+            day_end,
+            skip_weekends: true,
+            max_occurrences: None,
+        })
+    }
+
+    pub fn skip_weekends(mut self, skip_weekends: bool) -> Self {
+        self.skip_weekends = skip_weekends;
+        self
+    }
+
+    // Stop after `max_occurrences` fires, counting the one at the start time
+    pub fn with_max_occurrences(mut self, max_occurrences: u32) -> Self {
+        self.max_occurrences = Some(max_occurrences);
+        self
+    }
+
+    // Business window for the given date, if it is a working day
+    fn business_window(&self, date: NaiveDate) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        if self.skip_weekends && is_weekend(date) {
+            return None;
+        }
+        Some((
+            date.and_time(self.day_start).and_utc(),
+            date.and_time(self.day_end).and_utc(),
+        ))
+    }
+
+    // Length of a business day's window
+    fn window_millis(&self) -> u64 {
+        (self.day_end - self.day_start).num_milliseconds() as u64
+    }
+
+    // First working day after `date`
+    fn next_business_day(&self, date: NaiveDate) -> Option<NaiveDate> {
+        let mut next = date.succ_opt()?;
+        while self.business_window(next).is_none() {
+            next = next.succ_opt()?;
+        }
+        Some(next)
+    }
+
+    // Working day `days` working days on from the working day `date`, a week of them at a time
+    fn add_business_days(&self, date: NaiveDate, days: u64) -> Option<NaiveDate> {
+        if !self.skip_weekends {
+            return date.checked_add_days(chrono::Days::new(days));
+        }
+        let mut date = date.checked_add_days(chrono::Days::new(days / 5 * 7))?;
+        for _ in 0..days % 5 {
+            date = self.next_business_day(date)?;
+        }
+        Some(date)
+    }
+
+    // Working days from `from` up to, but not including, `to`
+    fn business_days_between(&self, from: NaiveDate, to: NaiveDate) -> u64 {
+        let days = (to - from).num_days().max(0) as u64;
+        if !self.skip_weekends {
+            return days;
+        }
+        let rest = (0..days % 7)
+            .filter_map(|day| from.checked_add_days(chrono::Days::new(days / 7 * 7 + day)))
+            .filter(|date| !is_weekend(*date))
+            .count();
+        days / 7 * 5 + rest as u64
+    }
+
+    // Milliseconds of business time on `date` between `from` and `to`
+    fn business_millis_on(&self, date: NaiveDate, from: DateTime<Utc>, to: DateTime<Utc>) -> u64 {
+        self.business_window(date)
+            .map(|(open, close)| (close.min(to) - open.max(from)).num_milliseconds().max(0) as u64)
+            .unwrap_or_default()
+    }
+
+    // Milliseconds of business time elapsed between `from` and `to`
+    fn business_millis_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> u64 {
+        let (first, last) = (from.date_naive(), to.date_naive());
+        if first >= last {
+            return self.business_millis_on(first, from, to);
+        }
+
+        // The partial first and last days, and every working day in between in full
+        let Some(second) = first.succ_opt() else {
+            return 0;
+        };
+        self.business_millis_on(first, from, to)
+            + self.business_days_between(second, last) * self.window_millis()
+            + self.business_millis_on(last, from, to)
+    }
+
+    // Wall-clock instant reached after spending `millis` of business time from `from`
+    fn advance(&self, from: DateTime<Utc>, millis: u64) -> Option<DateTime<Utc>> {
+        let mut remaining = millis;
+        let date = from.date_naive();
+
+        if let Some((open, close)) = self.business_window(date) {
+            let begin = open.max(from);
+            if close > begin {
+                let available = (close - begin).num_milliseconds() as u64;
+                if remaining <= available {
+                    return begin
+                        .checked_add_signed(chrono::TimeDelta::milliseconds(remaining as i64));
+                }
+                remaining -= available;
+            }
+        }
+
+        // Skip the whole working days used up, leaving the rest, up to a full window, for the
+        // day the time runs out on
+        let window = self.window_millis();
+        let whole_days = remaining.saturating_sub(1) / window;
+        let date = self.add_business_days(self.next_business_day(date)?, whole_days)?;
+        let (open, _) = self.business_window(date)?;
+        open.checked_add_signed(chrono::TimeDelta::milliseconds(
+            (remaining - whole_days * window) as i64,
+        ))
+    }
+}
+
+impl Schedule for BusinessHoursIntervalSchedule {
+    fn describe(&self) -> String {
+        let mut description = format!(
+            "every {} of business time between {} and {} from {}",
+            format_duration(self.interval),
+            self.day_start,
+            self.day_end,
+            self.start_time
+        );
+        if let Some(max) = self.max_occurrences {
+            description.push_str(&format!(", {max} times"));
+        }
+        description
+    }
+
+    fn is_satisfiable(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    fn validate(&self) -> Result<(), SchedulerError> {
+        match self.max_occurrences {
+            Some(0) => Err(SchedulerError::InvalidRepetition),
+            _ => Ok(()),
+        }
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if !self.is_satisfiable() {
+            return None;
+        }
+
+        // A start outside business hours fires when the next business window opens
+        let first = self.advance(self.start_time, 0)?;
+        if after < first {
+            return Some(first);
+        }
+
+        let interval_millis = self.interval.as_millis() as u64;
+        let elapsed = self.business_millis_between(first, after);
+        let intervals_passed = elapsed / interval_millis;
+
+        // Occurrence index `intervals_passed + 1`, with the start time as index 0
+        if self
+            .max_occurrences
+            .is_some_and(|max| intervals_passed + 1 >= max as u64)
+        {
+            return None;
+        }
+
+        self.advance(first, interval_millis * (intervals_passed + 1))
+    }
+}
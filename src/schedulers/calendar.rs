@@ -0,0 +1,59 @@
+use super::*;
+use std::collections::{BTreeMap, BTreeSet};
+
+// Named set of working days that many schedules can share, eg: a country's bank holidays
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calendar {
+    name: String,
+    weekend: Vec<Weekday>,
+    holidays: BTreeSet<NaiveDate>,
+    // Days whose trading session ends early, and when
+    early_closes: BTreeMap<NaiveDate, NaiveTime>,
+}
+
+impl Calendar {
+    // Saturday and Sunday off, with no holidays until some are added
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            weekend: vec![Weekday::Sat, Weekday::Sun],
+            holidays: BTreeSet::new(),
+            early_closes: BTreeMap::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Replace the weekend days, eg: Friday and Saturday
+    pub fn with_weekend(mut self, weekend: impl IntoIterator<Item = Weekday>) -> Self {
+        self.weekend = weekend.into_iter().collect();
+        self
+    }
+
+    pub fn with_holidays(mut self, holidays: impl IntoIterator<Item = NaiveDate>) -> Self {
+        self.holidays.extend(holidays);
+        self
+    }
+
+    // Close early on `date`, eg: a half day before a holiday
+    pub fn with_early_close(mut self, date: NaiveDate, close: NaiveTime) -> Self {
+        self.early_closes.insert(date, close);
+        self
+    }
+
+    pub fn early_close(&self, date: NaiveDate) -> Option<NaiveTime> {
+        self.early_closes.get(&date).copied()
+    }
+
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        !self.weekend.contains(&date.weekday()) && !self.holidays.contains(&date)
+    }
+}
+
+impl HolidayCalendar for Calendar {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.holidays.contains(&date)
+    }
+}
@@ -0,0 +1,69 @@
+use super::*;
+
+// Schedule dropping occurrences further than a fixed horizon past the first query time.
+// Later queries keep that same limit, so stepping through occurrences can't push it forward
+pub struct CappedHorizonSchedule {
+    inner: Box<dyn Schedule>,
+    horizon: Duration,
+    limit: Cell<Option<DateTime<Utc>>>,
+}
+
+impl CappedHorizonSchedule {
+    pub fn new(inner: Box<dyn Schedule>, horizon: Duration) -> Self {
+        Self {
+            inner,
+            horizon,
+            limit: Cell::new(None),
+        }
+    }
+
+    // Latest occurrence handed out, fixed by the first query
+    fn limit(&self, after: DateTime<Utc>) -> DateTime<Utc> {
+        if let Some(limit) = self.limit.get() {
+            return limit;
+        }
+        let limit = chrono::TimeDelta::from_std(self.horizon)
+            .ok()
+            .and_then(|horizon| after.checked_add_signed(horizon))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        self.limit.set(Some(limit));
+        limit
+    }
+}
+
+impl Schedule for CappedHorizonSchedule {
+    fn is_satisfiable(&self) -> bool {
+        self.inner.is_satisfiable()
+    }
+
+    fn validate(&self) -> Result<(), SchedulerError> {
+        self.inner.validate()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} within {}",
+            self.inner.describe(),
+            format_duration(self.horizon)
+        )
+    }
+
+    fn is_finished(&self, after: DateTime<Utc>) -> bool {
+        self.inner.is_finished(after) || self.limit.get().is_some_and(|limit| after >= limit)
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let limit = self.limit(after);
+        self.inner
+            .next_occurrence(after)
+            .filter(|next| *next <= limit)
+    }
+
+    fn record_fire(&self, time: DateTime<Utc>) {
+        self.inner.record_fire(time);
+    }
+
+    fn remaining_occurrences(&self) -> Option<u32> {
+        self.inner.remaining_occurrences()
+    }
+}
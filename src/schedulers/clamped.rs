@@ -0,0 +1,83 @@
+use super::*;
+
+// Schedule moving each of another schedule's occurrences into a daily time-of-day window, eg:
+// at sunset, but never before 18:00 nor after 21:00
+pub struct ClampedSchedule {
+    inner: Box<dyn Schedule>,
+    earliest: NaiveTime,
+    latest: NaiveTime,
+}
+
+impl ClampedSchedule {
+    pub fn new(
+        inner: Box<dyn Schedule>,
+        earliest: NaiveTime,
+        latest: NaiveTime,
+    ) -> Result<Self, SchedulerError> {
+        if earliest > latest {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+        Ok(Self {
+            inner,
+            earliest,
+            latest,
+        })
+    }
+
+    fn clamp(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        let date = time.date_naive();
+        time.clamp(
+            date.and_time(self.earliest).and_utc(),
+            date.and_time(self.latest).and_utc(),
+        )
+    }
+}
+
+impl Schedule for ClampedSchedule {
+    fn is_satisfiable(&self) -> bool {
+        self.inner.is_satisfiable()
+    }
+
+    fn validate(&self) -> Result<(), SchedulerError> {
+        self.inner.validate()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} between {} and {}",
+            self.inner.describe(),
+            self.earliest.format("%H:%M"),
+            self.latest.format("%H:%M")
+        )
+    }
+
+    fn is_finished(&self, after: DateTime<Utc>) -> bool {
+        self.inner.is_finished(after)
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut probe = after;
+
+        // Clamping pulls late occurrences back, so skip those landing at or before `after`
+        loop {
+            let next = self.inner.next_occurrence(probe)?;
+            let clamped = self.clamp(next);
+            if clamped > after {
+                return Some(clamped);
+            }
+            if next <= probe {
+                return None;
+            }
+            probe = next;
+        }
+    }
+
+    // The clamp can't be undone, so the inner schedule hears of the clamped time
+    fn record_fire(&self, time: DateTime<Utc>) {
+        self.inner.record_fire(time);
+    }
+
+    fn remaining_occurrences(&self) -> Option<u32> {
+        self.inner.remaining_occurrences()
+    }
+}
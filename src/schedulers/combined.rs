@@ -1,18 +1,55 @@
 use super::*;
 
+// Why a combined schedule has nothing to fire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombinedNoneReason {
+    // Every child is done (or the global cap is reached), so the job can be dropped
+    AllFinished,
+    // Children will fire again, just not within the probe window
+    NoneSoonWithin(Duration),
+}
+
 // Combined schedule
 pub struct CombinedSchedule {
     schedules: Vec<Box<dyn Schedule>>,
+    global_max: Option<OccurrenceCap>,
 }
 
 impl CombinedSchedule {
     pub fn new(schedules: Vec<Box<dyn Schedule>>) -> Self {
-        Self { schedules }
+        Self {
+            schedules,
+            global_max: None,
+        }
     }
-}
 
-impl Schedule for CombinedSchedule {
-    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    // Cap the total number of occurrences across all child schedules, counting each one handed
+    // out through record_fire, eg: fired by a job. Asking for occurrences doesn't count
+    pub fn with_global_max(mut self, global_max: u32) -> Self {
+        self.global_max = Some(OccurrenceCap::new(global_max));
+        self
+    }
+
+    // Explain an empty probe window, or None if something fires within it
+    pub fn explain_none(
+        &self,
+        after: DateTime<Utc>,
+        probe_window: Duration,
+    ) -> Option<CombinedNoneReason> {
+        if self.is_finished(after) {
+            return Some(CombinedNoneReason::AllFinished);
+        }
+        match self.next_occurrence(after) {
+            // A child with nothing to report right now, eg: gated off, may fire later
+            None => Some(CombinedNoneReason::NoneSoonWithin(probe_window)),
+            Some(next) if next > after + probe_window => {
+                Some(CombinedNoneReason::NoneSoonWithin(probe_window))
+            }
+            Some(_) => None,
+        }
+    }
+
+    fn earliest_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
         let mut earliest: Option<DateTime<Utc>> = None;
 
         for schedule in &self.schedules {
@@ -31,3 +68,81 @@ impl Schedule for CombinedSchedule {
         earliest
     }
 }
+
+impl Schedule for CombinedSchedule {
+    fn describe(&self) -> String {
+        let descriptions: Vec<String> = self
+            .schedules
+            .iter()
+            .map(|schedule| schedule.describe())
+            .collect();
+        format!("any of [{}]", descriptions.join(", "))
+    }
+
+    // Finished once the global cap is used up or every child is done
+    fn is_finished(&self, after: DateTime<Utc>) -> bool {
+        self.global_max
+            .as_ref()
+            .is_some_and(OccurrenceCap::is_exhausted)
+            || self
+                .schedules
+                .iter()
+                .all(|schedule| schedule.is_finished(after))
+    }
+
+    fn is_satisfiable(&self) -> bool {
+        self.schedules
+            .iter()
+            .any(|schedule| schedule.is_satisfiable())
+    }
+
+    // Nothing to combine is a misconfiguration, otherwise the first child's error when none of
+    // them can fire
+    fn validate(&self) -> Result<(), SchedulerError> {
+        if self.is_satisfiable() {
+            return Ok(());
+        }
+        match self.schedules.first() {
+            Some(schedule) => schedule.validate(),
+            None => Err(SchedulerError::InvalidConfiguration),
+        }
+    }
+
+    fn previous_occurrence(&self, before: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let latest = |before| {
+            self.schedules
+                .iter()
+                .filter_map(|schedule| schedule.previous_occurrence(before))
+                .max()
+        };
+        match &self.global_max {
+            Some(cap) => cap.previous(before, &latest),
+            None => latest(before),
+        }
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match &self.global_max {
+            Some(cap) => cap.next(after, &|t| self.earliest_occurrence(t)),
+            None => self.earliest_occurrence(after),
+        }
+    }
+
+    fn record_fire(&self, time: DateTime<Utc>) {
+        if let Some(cap) = &self.global_max {
+            cap.record(time);
+        }
+
+        // Only the children the fire is an occurrence of count it
+        let just_before = time - chrono::TimeDelta::nanoseconds(1);
+        for schedule in &self.schedules {
+            if schedule.next_occurrence(just_before) == Some(time) {
+                schedule.record_fire(time);
+            }
+        }
+    }
+
+    fn remaining_occurrences(&self) -> Option<u32> {
+        self.global_max.as_ref().map(OccurrenceCap::remaining)
+    }
+}
@@ -1,13 +1,53 @@
 use super::*;
 
-// Cron-like schedule
+// Day of month selector for CronSchedule::day_spec
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DaySpec {
+    // A fixed day of the month, as with CronSchedule::day
+    Day(u32),
+    // The last day of the month and the N days before it, eg: L-2 is the last three days
+    LastMinus(u8),
+    // The Monday to Friday closest to a day, within the same month, eg: 15W
+    NearestWeekday(u32),
+    // The last Monday to Friday of the month, eg: LW
+    LastWeekday,
+}
+
+// Day of week selector for CronSchedule::weekday_spec, counting from Monday = 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WeekdaySpec {
+    // Every such weekday, as with CronSchedule::weekday
+    Every(u32),
+    // The nth such weekday of the month, eg: Nth(5, 3) is the third Saturday
+    Nth(u32, u8),
+    // The last such weekday of the month
+    Last(u32),
+}
+
+// Cron-like schedule. With serde, its zone is stored by IANA name, eg: America/New_York
 #[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CronSchedule {
-    minute: Option<u32>,
-    hour: Option<u32>,
-    day: Option<u32>,
-    month: Option<u32>,
-    weekday: Option<u32>,
+    second: Option<Vec<u32>>,
+    minute: Option<Vec<u32>>,
+    hour: Option<Vec<u32>>,
+    day: Option<Vec<u32>>,
+    day_step: Option<u32>,
+    last_days: Option<u8>,
+    nearest_weekday: Option<DaySpec>,
+    month: Option<Vec<u32>>,
+    weekday: Option<Vec<u32>>,
+    weekday_spec: Option<WeekdaySpec>,
+    // Parsed day-of-month and day-of-week lists match either one, as in Vixie cron
+    day_or_weekday: bool,
+    year: Option<Vec<i32>>,
+    second_jitter: Option<u32>,
+    jitter_seed: Option<u64>,
+    #[cfg_attr(feature = "serde", serde(rename = "timezone"))]
+    wall_clock: WallClock,
+    dst_policy: DstPolicy,
 }
 
 impl CronSchedule {
@@ -17,192 +57,1077 @@ impl CronSchedule {
         }
     }
 
-    pub fn minute(mut self, minute: u32) -> Result<Self, SchedulerError> {
-        if minute >= 60 {
-            return Err(SchedulerError::InvalidConfiguration);
+    // Top of every hour, as with @hourly
+    pub fn hourly() -> Self {
+        Self {
+            minute: Some(vec![0]),
+            ..Self::new()
         }
-        self.minute = Some(minute);
-        Ok(self)
     }
 
-    pub fn hour(mut self, hour: u32) -> Result<Self, SchedulerError> {
-        if hour >= 24 {
-            return Err(SchedulerError::InvalidConfiguration);
+    // Midnight every day, as with @daily
+    pub fn daily() -> Self {
+        Self {
+            hour: Some(vec![0]),
+            ..Self::hourly()
+        }
+    }
+
+    // Midnight every Sunday, as with @weekly
+    pub fn weekly() -> Self {
+        Self {
+            weekday: Some(vec![6]),
+            ..Self::daily()
+        }
+    }
+
+    // Midnight on the 1st of every month, as with @monthly
+    pub fn monthly() -> Self {
+        Self {
+            day: Some(vec![1]),
+            ..Self::daily()
+        }
+    }
+
+    // Midnight on the last day of every month, whatever its length, as with a day of L
+    pub fn month_end() -> Self {
+        Self {
+            last_days: Some(0),
+            ..Self::daily()
+        }
+    }
+
+    // Midnight on the 1st of January, as with @yearly
+    pub fn yearly() -> Self {
+        Self {
+            month: Some(vec![1]),
+            ..Self::monthly()
+        }
+    }
+
+    // Monday to Friday at hour:minute, as with `m h * * 1-5`
+    pub fn weekday_mornings(hour: u32, minute: u32) -> Result<Self, SchedulerError> {
+        Self::new().hour(hour)?.minute(minute)?.weekdays(0..5)
+    }
+
+    // Validate every field in one call instead of chaining fallible builders
+    pub fn from_fields(
+        minute: Option<u32>,
+        hour: Option<u32>,
+        day: Option<u32>,
+        month: Option<u32>,
+        weekday: Option<u32>,
+    ) -> Result<Self, SchedulerError> {
+        let mut schedule = Self::new();
+        if let Some(minute) = minute {
+            schedule = schedule.minute(minute)?;
+        }
+        if let Some(hour) = hour {
+            schedule = schedule.hour(hour)?;
+        }
+        if let Some(day) = day {
+            schedule = schedule.day(day)?;
         }
-        self.hour = Some(hour);
+        if let Some(month) = month {
+            schedule = schedule.month(month)?;
+        }
+        if let Some(weekday) = weekday {
+            schedule = schedule.weekday(weekday)?;
+        }
+        Ok(schedule)
+    }
+
+    // Fire at this second of each matched minute instead of at the top of the minute
+    pub fn second(self, second: u32) -> Result<Self, SchedulerError> {
+        self.seconds([second])
+    }
+
+    pub fn seconds(
+        mut self,
+        seconds: impl IntoIterator<Item = u32>,
+    ) -> Result<Self, SchedulerError> {
+        let seconds = value_set(seconds, 0..60)?;
+        check_within_minute(&seconds, self.second_jitter)?;
+        self.second = Some(seconds);
+        Ok(self)
+    }
+
+    pub fn minute(self, minute: u32) -> Result<Self, SchedulerError> {
+        self.minutes([minute])
+    }
+
+    // Fire on any of the given minutes, eg: minutes([0, 30]) or minutes(0..15)
+    pub fn minutes(
+        mut self,
+        minutes: impl IntoIterator<Item = u32>,
+    ) -> Result<Self, SchedulerError> {
+        self.minute = Some(value_set(minutes, 0..60)?);
+        Ok(self)
+    }
+
+    pub fn hour(self, hour: u32) -> Result<Self, SchedulerError> {
+        self.hours([hour])
+    }
+
+    pub fn hours(mut self, hours: impl IntoIterator<Item = u32>) -> Result<Self, SchedulerError> {
+        self.hour = Some(value_set(hours, 0..24)?);
         Ok(self)
     }
 
-    pub fn day(mut self, day: u32) -> Result<Self, SchedulerError> {
-        if day == 0 || day > 31 {
+    pub fn day(self, day: u32) -> Result<Self, SchedulerError> {
+        self.days([day])
+    }
+
+    pub fn days(mut self, days: impl IntoIterator<Item = u32>) -> Result<Self, SchedulerError> {
+        self.day = Some(value_set(days, 1..32)?);
+        Ok(self)
+    }
+
+    pub fn day_spec(self, spec: DaySpec) -> Result<Self, SchedulerError> {
+        match spec {
+            DaySpec::Day(day) => self.day(day),
+            // Every month has at least 28 days, so the window always fits
+            DaySpec::LastMinus(offset) if offset >= 28 => Err(SchedulerError::InvalidConfiguration),
+            DaySpec::LastMinus(offset) => Ok(Self {
+                last_days: Some(offset),
+                ..self
+            }),
+            DaySpec::NearestWeekday(day) if day == 0 || day > 31 => {
+                Err(SchedulerError::InvalidConfiguration)
+            }
+            DaySpec::NearestWeekday(_) | DaySpec::LastWeekday => Ok(Self {
+                nearest_weekday: Some(spec),
+                ..self
+            }),
+        }
+    }
+
+    // Every `step` days of the month counting from the 1st, restarting each month
+    pub fn day_step(mut self, step: u32) -> Result<Self, SchedulerError> {
+        if step == 0 || step > 31 {
             return Err(SchedulerError::InvalidConfiguration);
         }
-        self.day = Some(day);
+        self.day_step = Some(step);
         Ok(self)
     }
 
-    pub fn month(mut self, month: u32) -> Result<Self, SchedulerError> {
-        if month == 0 || month > 12 {
-            return Err(SchedulerError::InvalidConfiguration);
+    pub fn month(self, month: u32) -> Result<Self, SchedulerError> {
+        self.months([month])
+    }
+
+    pub fn months(mut self, months: impl IntoIterator<Item = u32>) -> Result<Self, SchedulerError> {
+        self.month = Some(value_set(months, 1..13)?);
+        Ok(self)
+    }
+
+    pub fn weekday(self, weekday: u32) -> Result<Self, SchedulerError> {
+        self.weekdays([weekday])
+    }
+
+    pub fn weekdays(
+        mut self,
+        weekdays: impl IntoIterator<Item = u32>,
+    ) -> Result<Self, SchedulerError> {
+        self.weekday = Some(value_set(weekdays, 0..7)?);
+        Ok(self)
+    }
+
+    // Same as `weekdays`, taking chrono weekdays, eg: [Weekday::Mon, Weekday::Wed, Weekday::Fri]
+    pub fn on_weekdays(
+        self,
+        weekdays: impl IntoIterator<Item = Weekday>,
+    ) -> Result<Self, SchedulerError> {
+        self.weekdays(weekdays.into_iter().map(weekday_to_cron))
+    }
+
+    pub fn weekday_spec(self, spec: WeekdaySpec) -> Result<Self, SchedulerError> {
+        match spec {
+            WeekdaySpec::Every(weekday) => self.weekday(weekday),
+            // No month has a sixth of any weekday
+            WeekdaySpec::Nth(_, nth) if nth == 0 || nth > 5 => {
+                Err(SchedulerError::InvalidConfiguration)
+            }
+            WeekdaySpec::Nth(weekday, _) | WeekdaySpec::Last(weekday) => Ok(Self {
+                weekday_spec: Some(spec),
+                ..self.weekday(weekday)?
+            }),
         }
-        self.month = Some(month);
+    }
+
+    pub fn year(self, year: i32) -> Result<Self, SchedulerError> {
+        self.years([year])
+    }
+
+    // Restrict to the given years, within the 1970 to 2099 range Quartz allows
+    pub fn years(mut self, years: impl IntoIterator<Item = i32>) -> Result<Self, SchedulerError> {
+        self.year = Some(value_set(years, 1970..2100)?);
         Ok(self)
     }
 
-    pub fn weekday(mut self, weekday: u32) -> Result<Self, SchedulerError> {
-        if weekday >= 7 {
+    // Fire up to `max_seconds` into each matched minute so jobs on the same minute spread out
+    pub fn with_second_jitter(mut self, max_seconds: u32) -> Result<Self, SchedulerError> {
+        if max_seconds >= 60 {
             return Err(SchedulerError::InvalidConfiguration);
         }
-        self.weekday = Some(weekday);
+        check_within_minute(self.second.as_deref().unwrap_or(&[0]), Some(max_seconds))?;
+        self.second_jitter = Some(max_seconds);
+        self.jitter_seed.get_or_insert_with(|| rand::rng().random());
         Ok(self)
     }
+
+    // Match fields against the machine's wall clock rather than UTC. The OS zone is read each
+    // time an occurrence is computed, so a timezone change is picked up by the next poll
+    pub fn with_local_timezone(mut self) -> Self {
+        self.wall_clock = WallClock::Local;
+        self
+    }
+
+    // Match fields against the wall clock in a named zone, eg: 9am in America/New_York
+    #[cfg(feature = "chrono-tz")]
+    pub fn with_timezone(mut self, tz: chrono_tz::Tz) -> Self {
+        self.wall_clock = WallClock::Named(tz);
+        self
+    }
+
+    // Choose how local-time fields treat times a DST change skips or repeats
+    pub fn with_dst_policy(mut self, policy: DstPolicy) -> Self {
+        self.dst_policy = policy;
+        self
+    }
+
+    // Seed the jitter so offsets are reproducible across runs
+    pub fn with_jitter_seed(mut self, seed: u64) -> Self {
+        self.jitter_seed = Some(seed);
+        self
+    }
+
+    // RFC 5545 RRULE for calendar export, or None when the fields can't be expressed as one
+    pub fn to_icalendar_rrule(&self) -> Option<String> {
+        // Day steps restart each month, which no RRULE interval reproduces, BYMONTHDAY
+        // with BYDAY only matches days satisfying both, and the zone belongs on DTSTART
+        if self.day_step.is_some()
+            || self.day_or_weekday
+            || self.wall_clock != WallClock::Utc
+            || self.last_days.is_some()
+            || !self.is_standard()
+            || !self.is_satisfiable()
+        {
+            return None;
+        }
+        let minute = self.minute.as_deref()?;
+
+        // Frequency follows the coarsest constrained field; every finer field must be fixed
+        let freq = match (&self.month, &self.day, &self.weekday, &self.hour) {
+            (Some(_), _, _, Some(_)) => "YEARLY",
+            (None, Some(_), _, Some(_)) => "MONTHLY",
+            (None, None, Some(_), Some(_)) => "WEEKLY",
+            (None, None, None, Some(_)) => "DAILY",
+            (None, None, None, None) => "HOURLY",
+            _ => return None,
+        };
+
+        let mut parts = vec![format!("FREQ={freq}")];
+        let list = |values: &[u32]| {
+            let values: Vec<String> = values.iter().map(u32::to_string).collect();
+            values.join(",")
+        };
+        if let Some(months) = &self.month {
+            parts.push(format!("BYMONTH={}", list(months)));
+        }
+        if let Some(days) = &self.day {
+            parts.push(format!("BYMONTHDAY={}", list(days)));
+        }
+        if let Some(weekdays) = &self.weekday {
+            const DAYS: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+            let days: Vec<&str> = weekdays.iter().map(|&day| DAYS[day as usize]).collect();
+            parts.push(format!("BYDAY={}", days.join(",")));
+        }
+        if let Some(hours) = &self.hour {
+            parts.push(format!("BYHOUR={}", list(hours)));
+        }
+        parts.push(format!("BYMINUTE={}", list(minute)));
+        if let Some(seconds) = &self.second {
+            parts.push(format!("BYSECOND={}", list(seconds)));
+        }
+
+        Some(format!("RRULE:{}", parts.join(";")))
+    }
 }
 
 impl Schedule for CronSchedule {
+    fn matches(&self, time: DateTime<Utc>, tolerance: Duration) -> bool {
+        if self.second_jitter.is_some() || self.wall_clock != WallClock::Utc {
+            return occurs_within(self, time, tolerance);
+        }
+        self.fields_match(time) || (!tolerance.is_zero() && occurs_within(self, time, tolerance))
+    }
+
+    fn to_cron_expression(&self) -> Option<String> {
+        // Standard cron has no seconds or way to spread fires within a minute, nor an L-N day,
+        // and it reads a listed day and weekday as either one rather than both. The expression
+        // has nowhere to carry a zone, so only UTC schedules export, and a day field holds
+        // either listed days or a step but not both
+        let both_days = self.day.is_some() && self.weekday.is_some() && !self.day_or_weekday;
+        let stepped_days = self.day.is_some() && self.day_step.is_some();
+        if self.second.is_some()
+            || self.second_jitter.is_some()
+            || self.last_days.is_some()
+            || both_days
+            || stepped_days
+            || self.wall_clock != WallClock::Utc
+            || !self.is_standard()
+        {
+            return None;
+        }
+        Some(self.cron_fields())
+    }
+
+    fn describe(&self) -> String {
+        let mut description = format!("cron {}", self.cron_fields());
+        description.push_str(&self.wall_clock.describe());
+        if let Some(max_seconds) = self.second_jitter {
+            description.push_str(&format!(" with up to {max_seconds}s jitter"));
+        }
+        description
+    }
+
+    fn is_satisfiable(&self) -> bool {
+        // Every month has each weekday
+        if self.day_or_weekday {
+            return true;
+        }
+        let day_allowed = |day: u32| {
+            self.day.as_ref().is_none_or(|days| days.contains(&day))
+                && self
+                    .day_step
+                    .is_none_or(|step| (day - 1).is_multiple_of(step))
+        };
+        let all_months: Vec<u32> = (1..=12).collect();
+        let months = self.month.as_deref().unwrap_or(&all_months);
+
+        // Some month length in range must put a listed or stepped day within the last days
+        if let Some(offset) = self.last_days {
+            return months.iter().any(|&month| {
+                let (shortest, longest) = month_lengths(month);
+                (shortest..=longest)
+                    .any(|length| (length - offset as u32..=length).any(day_allowed))
+            });
+        }
+
+        // Some allowed day must exist in a constrained month (leap years included)
+        months
+            .iter()
+            .any(|&month| (1..=month_lengths(month).1).any(day_allowed))
+    }
+
     fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
-        let mut next = after;
+        if self.second.is_none() && self.second_jitter.is_none() {
+            return self.next_matching_minute(after);
+        }
+        let seconds = self.second.as_deref().unwrap_or(&[0]);
+
+        // The minute containing `after` may still have a fire ahead
+        let mut probe = after - Duration::from_secs(60);
+        loop {
+            let minute = self.next_matching_minute(probe)?;
+            let jitter = self.second_jitter.map_or(Duration::ZERO, |max_seconds| {
+                self.jitter_for(minute, max_seconds)
+            });
+            let fire = seconds
+                .iter()
+                .map(|&second| minute + Duration::from_secs(second as u64) + jitter)
+                .find(|&fire| fire > after);
+            if fire.is_some() {
+                return fire;
+            }
+            probe = minute;
+        }
+    }
+}
+
+// Parse a classic five-field expression: minute hour day-of-month month day-of-week,
+// one of the @hourly, @daily, @weekly, @monthly and @yearly shorthands, or a Quartz
+// expression with leading seconds, an optional year, and the L, W and # rules. Fields may be
+// followed by a timezone name to read them in, eg: `0 9 * * 1-5 America/New_York`
+impl std::str::FromStr for CronSchedule {
+    type Err = SchedulerError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        if let Some(shorthand) = expression.trim().strip_prefix('@') {
+            return match shorthand.to_ascii_lowercase().as_str() {
+                "hourly" => Ok(Self::hourly()),
+                "daily" | "midnight" => Ok(Self::daily()),
+                "weekly" => Ok(Self::weekly()),
+                "monthly" => Ok(Self::monthly()),
+                "yearly" | "annually" => Ok(Self::yearly()),
+                _ => Err(invalid_field("expression", expression)),
+            };
+        }
+
+        let mut fields: Vec<&str> = expression.split_whitespace().collect();
+        let timezone = match fields[..] {
+            [_, _, _, _, _, .., last] if is_timezone_name(last) => fields.pop(),
+            _ => None,
+        };
+        // Quartz puts seconds first and takes an optional trailing year
+        let (second, [minute, hour, day, month, weekday], year) = match fields[..] {
+            [minute, hour, day, month, weekday] => {
+                (None, [minute, hour, day, month, weekday], None)
+            }
+            [second, minute, hour, day, month, weekday] => {
+                (Some(second), [minute, hour, day, month, weekday], None)
+            }
+            [second, minute, hour, day, month, weekday, year] => (
+                Some(second),
+                [minute, hour, day, month, weekday],
+                Some(year),
+            ),
+            _ => return Err(invalid_field("expression", expression)),
+        };
+
+        let mut schedule = Self::new();
+        if let Some(second) = second {
+            if let Some(values) = parse_field("second", second, &[], 0..=59)? {
+                schedule = schedule
+                    .seconds(values)
+                    .map_err(|_| invalid_field("second", second))?;
+            }
+        }
+        if let Some(values) = parse_field("minute", minute, &[], 0..=59)? {
+            schedule = schedule
+                .minutes(values)
+                .map_err(|_| invalid_field("minute", minute))?;
+        }
+        if let Some(values) = parse_field("hour", hour, &[], 0..=23)? {
+            schedule = schedule
+                .hours(values)
+                .map_err(|_| invalid_field("hour", hour))?;
+        }
+        schedule = parse_day(schedule, day)?;
+        if let Some(values) = parse_field("month", month, &MONTH_NAMES, 1..=12)? {
+            schedule = schedule
+                .months(values)
+                .map_err(|_| invalid_field("month", month))?;
+        }
+        schedule = parse_weekday(schedule, weekday)?;
+        schedule.day_or_weekday =
+            schedule.day.is_some() && schedule.weekday.is_some() && schedule.weekday_spec.is_none();
+        if let Some(year) = year {
+            if let Some(values) = parse_field("year", year, &[], 1970..=2099)? {
+                schedule = schedule
+                    .years(values.into_iter().map(|value| value as i32))
+                    .map_err(|_| invalid_field("year", year))?;
+            }
+        }
+        if let Some(timezone) = timezone {
+            schedule = in_named_timezone(schedule, timezone)?;
+        }
+
+        Ok(schedule)
+    }
+}
+
+// Names accepted in place of numbers, matching the number they sit at
+const MONTH_NAMES: [&str; 13] = [
+    "", "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+// Whether a trailing token names a timezone rather than a field, eg: America/New_York or UTC.
+// Area/Location names count even when unknown, so a typo is reported as a bad timezone
+fn is_timezone_name(token: &str) -> bool {
+    let area_location = token.split_once('/').is_some_and(|(area, location)| {
+        area.starts_with(|c: char| c.is_ascii_alphabetic())
+            && location.starts_with(|c: char| c.is_ascii_alphabetic())
+    });
+    #[cfg(feature = "chrono-tz")]
+    let known = token.parse::<chrono_tz::Tz>().is_ok();
+    #[cfg(not(feature = "chrono-tz"))]
+    let known = false;
+    area_location || known
+}
+
+#[cfg(feature = "chrono-tz")]
+fn in_named_timezone(schedule: CronSchedule, name: &str) -> Result<CronSchedule, SchedulerError> {
+    let tz = name
+        .parse::<chrono_tz::Tz>()
+        .map_err(|_| invalid_field("timezone", name))?;
+    Ok(schedule.with_timezone(tz))
+}
+
+// Without the chrono-tz feature there are no named zones to read the fields in
+#[cfg(not(feature = "chrono-tz"))]
+fn in_named_timezone(_: CronSchedule, name: &str) -> Result<CronSchedule, SchedulerError> {
+    Err(invalid_field("timezone", name))
+}
+
+fn invalid_field(field: &'static str, value: &str) -> SchedulerError {
+    SchedulerError::InvalidCronField {
+        field,
+        value: value.to_string(),
+    }
+}
 
-        // Add 1 second to ensure we don't get the same time again
-        next += Duration::from_secs(1);
+// `*` for any value, otherwise a comma separated list of numbers or names within `range`,
+// each of which may be an `a-b` range, and `*`, `a` or `a-b` may be stepped with `/n`
+fn parse_field(
+    field: &'static str,
+    token: &str,
+    names: &[&str],
+    range: RangeInclusive<u32>,
+) -> Result<Option<Vec<u32>>, SchedulerError> {
+    // Quartz writes `?` for a field left to the other day field
+    if token == "*" || token == "?" {
+        return Ok(None);
+    }
+    let value = |text: &str| {
+        names
+            .iter()
+            .position(|name| !name.is_empty() && name.eq_ignore_ascii_case(text))
+            .map(|index| index as u32)
+            .or_else(|| text.parse().ok())
+            .filter(|value| range.contains(value))
+            .ok_or_else(|| invalid_field(field, token))
+    };
+
+    let mut values = Vec::new();
+    for part in token.split(',') {
+        let (base, step) = match part.split_once('/') {
+            Some((base, step)) => match step.parse() {
+                Ok(step) if step > 0 => (base, Some(step)),
+                _ => return Err(invalid_field(field, token)),
+            },
+            None => (part, None),
+        };
+        // A stepped single value runs to the end of the range, as in Vixie cron
+        let (start, end) = match base.split_once('-') {
+            _ if base == "*" && step.is_some() => (*range.start(), *range.end()),
+            Some((start, end)) => (value(start)?, value(end)?),
+            None if step.is_some() => (value(base)?, *range.end()),
+            None => (value(base)?, value(base)?),
+        };
+        if start > end {
+            return Err(invalid_field(field, token));
+        }
+        values.extend((start..=end).step_by(step.unwrap_or(1)));
+    }
+    Ok(Some(values))
+}
+
+// Sorted, deduplicated values, all of which must fall in `allowed`
+fn value_set<T: Ord>(
+    values: impl IntoIterator<Item = T>,
+    allowed: Range<T>,
+) -> Result<Vec<T>, SchedulerError> {
+    let mut values: Vec<T> = values.into_iter().collect();
+    if values.is_empty() || values.iter().any(|value| !allowed.contains(value)) {
+        return Err(SchedulerError::InvalidConfiguration);
+    }
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+// Jitter on top of the latest second must keep each fire within its matched minute
+fn check_within_minute(seconds: &[u32], jitter: Option<u32>) -> Result<(), SchedulerError> {
+    let latest = seconds.iter().max().copied().unwrap_or_default();
+    if latest + jitter.unwrap_or_default() > 59 {
+        return Err(SchedulerError::InvalidConfiguration);
+    }
+    Ok(())
+}
+
+// Smallest listed value at or above `from`
+fn next_in<T: Ord + Copy>(values: &[T], from: T) -> Option<T> {
+    values.iter().copied().find(|&value| value >= from)
+}
+
+// Comma separated values, with runs of three or more written as ranges, eg: "1-5,9"
+fn format_values(values: &[u32]) -> String {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    while start < values.len() {
+        let mut end = start;
+        while end + 1 < values.len() && values[end + 1] == values[end] + 1 {
+            end += 1;
+        }
+        if end - start >= 2 {
+            parts.push(format!("{}-{}", values[start], values[end]));
+            start = end + 1;
+        } else {
+            parts.push(values[start].to_string());
+            start += 1;
+        }
+    }
+    parts.join(",")
+}
+
+// Day of the month holding the weekday nearest a W rule's target day, without leaving the month
+fn nearest_weekday_day(time: DateTime<Utc>, spec: DaySpec) -> Option<u32> {
+    let length = days_in_month(time)?;
+    let target = match spec {
+        DaySpec::NearestWeekday(day) if day > length => return None,
+        DaySpec::NearestWeekday(day) => day,
+        _ => length,
+    };
+    Some(match time.date_naive().with_day(target)?.weekday() {
+        Weekday::Sat if target == 1 => 3,
+        Weekday::Sat => target - 1,
+        Weekday::Sun if target == length => target - 2,
+        Weekday::Sun => target + 1,
+        _ => target,
+    })
+}
+
+// Day of the month holding the nth or last given weekday, if the month has one
+fn nth_weekday_day(time: DateTime<Utc>, spec: WeekdaySpec) -> Option<u32> {
+    let length = days_in_month(time)?;
+    let first_weekday = time
+        .date_naive()
+        .with_day(1)?
+        .weekday()
+        .num_days_from_monday();
+    let first = |weekday: u32| 1 + (weekday + 7 - first_weekday) % 7;
+    match spec {
+        WeekdaySpec::Nth(weekday, nth) => {
+            Some(first(weekday) + 7 * (nth as u32 - 1)).filter(|&day| day <= length)
+        }
+        WeekdaySpec::Last(weekday) => Some(first(weekday) + 7 * ((length - first(weekday)) / 7)),
+        WeekdaySpec::Every(_) => None,
+    }
+}
+
+// Shortest and longest length of a month, leap years included
+fn month_lengths(month: u32) -> (u32, u32) {
+    match month {
+        2 => (28, 29),
+        4 | 6 | 9 | 11 => (30, 30),
+        _ => (31, 31),
+    }
+}
+
+// Day of month also accepts `*/N` steps, `L` / `L-N` for the last days, and `NW` / `LW`
+// for the nearest or last weekday
+fn parse_day(schedule: CronSchedule, token: &str) -> Result<CronSchedule, SchedulerError> {
+    let invalid = || invalid_field("day-of-month", token);
+
+    if token == "LW" {
+        return schedule.day_spec(DaySpec::LastWeekday);
+    }
+    if let Some(day) = token.strip_suffix('W') {
+        let day = day.parse().map_err(|_| invalid())?;
+        return schedule
+            .day_spec(DaySpec::NearestWeekday(day))
+            .map_err(|_| invalid());
+    }
+
+    if let Some(step) = token.strip_prefix("*/") {
+        let step = step.parse().map_err(|_| invalid())?;
+        return schedule.day_step(step).map_err(|_| invalid());
+    }
+    if let Some(offset) = token.strip_prefix('L') {
+        let offset = match offset.strip_prefix('-') {
+            Some(offset) => offset.parse().map_err(|_| invalid())?,
+            None if offset.is_empty() => 0,
+            None => return Err(invalid()),
+        };
+        return schedule
+            .day_spec(DaySpec::LastMinus(offset))
+            .map_err(|_| invalid());
+    }
+
+    match parse_field("day-of-month", token, &[], 1..=31)? {
+        Some(days) => schedule.days(days).map_err(|_| invalid()),
+        None => Ok(schedule),
+    }
+}
+
+// Day of week also accepts `N#K` for the Kth such weekday of the month and `NL` for the last
+fn parse_weekday(schedule: CronSchedule, token: &str) -> Result<CronSchedule, SchedulerError> {
+    let invalid = || invalid_field("day-of-week", token);
+    // Standard cron counts from Sunday = 0 (or 7), this crate from Monday = 0
+    let single = |text: &str| match parse_field("day-of-week", text, &WEEKDAY_NAMES, 0..=7) {
+        Ok(Some(values)) if values.len() == 1 => Ok((values[0] + 6) % 7),
+        _ => Err(invalid()),
+    };
+
+    let spec = if let Some((weekday, nth)) = token.split_once('#') {
+        WeekdaySpec::Nth(single(weekday)?, nth.parse().map_err(|_| invalid())?)
+    } else if let Some(weekday) = token.strip_suffix('L') {
+        WeekdaySpec::Last(single(weekday)?)
+    } else {
+        return match parse_field("day-of-week", token, &WEEKDAY_NAMES, 0..=7)? {
+            Some(values) => schedule
+                .weekdays(values.into_iter().map(|value| (value + 6) % 7))
+                .map_err(|_| invalid()),
+            None => Ok(schedule),
+        };
+    };
+    schedule.weekday_spec(spec).map_err(|_| invalid())
+}
+
+// Midnight at the start of the given date
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    date.and_time(NaiveTime::MIN).and_utc()
+}
+
+// Midnight on the first day of the given month
+fn start_of_month(year: i32, month: u32) -> Option<DateTime<Utc>> {
+    NaiveDate::from_ymd_opt(year, month, 1).map(start_of_day)
+}
+
+// Number of days in the month containing `time`
+fn days_in_month(time: DateTime<Utc>) -> Option<u32> {
+    let next_month = start_of_next_month(time)?;
+    Some((next_month - chrono::TimeDelta::days(1)).day())
+}
+
+// Midnight on the first day of the month following `time`
+fn start_of_next_month(time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if time.month() == 12 {
+        start_of_month(time.year() + 1, 1)
+    } else {
+        start_of_month(time.year(), time.month() + 1)
+    }
+}
+
+impl CronSchedule {
+    // Whether the fields avoid the Quartz W, # and L weekday rules and the year field
+    fn is_standard(&self) -> bool {
+        self.nearest_weekday.is_none() && self.weekday_spec.is_none() && self.year.is_none()
+    }
+
+    // Days fixed by the W, # and L weekday rules in the month containing `time`, with None
+    // for a rule that has no day in that month
+    fn resolved_days(&self, time: DateTime<Utc>) -> Vec<Option<u32>> {
+        let mut days = Vec::new();
+        if let Some(spec) = self.nearest_weekday {
+            days.push(nearest_weekday_day(time, spec));
+        }
+        if let Some(spec) = self.weekday_spec {
+            days.push(nth_weekday_day(time, spec));
+        }
+        days
+    }
+    // Standard five-field form, eg: "30 9 * * 1", with a leading seconds field when set
+    fn cron_fields(&self) -> String {
+        let field = |values: Option<&[u32]>| values.map_or(String::from("*"), format_values);
+        // Standard cron counts weekdays from Sunday = 0
+        let weekday = match self.weekday_spec {
+            Some(WeekdaySpec::Nth(weekday, nth)) => format!("{}#{nth}", (weekday + 1) % 7),
+            Some(WeekdaySpec::Last(weekday)) => format!("{}L", (weekday + 1) % 7),
+            _ => {
+                let weekdays = self.weekday.as_ref().map(|weekdays| {
+                    let mut weekdays: Vec<u32> = weekdays.iter().map(|day| (day + 1) % 7).collect();
+                    weekdays.sort_unstable();
+                    weekdays
+                });
+                field(weekdays.as_deref())
+            }
+        };
+        let day = match (
+            self.nearest_weekday,
+            &self.day,
+            self.day_step,
+            self.last_days,
+        ) {
+            (Some(DaySpec::NearestWeekday(day)), ..) => format!("{day}W"),
+            (Some(_), ..) => String::from("LW"),
+            (None, None, _, Some(0)) => String::from("L"),
+            (None, None, _, Some(offset)) => format!("L-{offset}"),
+            (None, None, Some(step), None) => format!("*/{step}"),
+            (None, day, _, _) => field(day.as_deref()),
+        };
+        let mut fields = format!(
+            "{} {} {} {} {}",
+            field(self.minute.as_deref()),
+            field(self.hour.as_deref()),
+            day,
+            field(self.month.as_deref()),
+            weekday
+        );
+
+        // Seconds lead a six-field form, and a year makes it seven as in Quartz
+        if self.second.is_some() || self.year.is_some() {
+            let seconds = self.second.as_deref().unwrap_or(&[0]);
+            fields = format!("{} {fields}", format_values(seconds));
+        }
+        if let Some(years) = &self.year {
+            let years: Vec<String> = years.iter().map(i32::to_string).collect();
+            fields = format!("{fields} {}", years.join(","));
+        }
+        fields
+    }
+
+    // Whether every constrained field matches `time` exactly
+    fn fields_match(&self, time: DateTime<Utc>) -> bool {
+        let matches = |field: &Option<Vec<u32>>, value: u32| {
+            field.as_ref().is_none_or(|f| f.contains(&value))
+        };
+
+        time.nanosecond() == 0
+            && self
+                .second
+                .as_deref()
+                .unwrap_or(&[0])
+                .contains(&time.second())
+            && matches(&self.minute, time.minute())
+            && matches(&self.hour, time.hour())
+            && self.days_match(time)
+            && self
+                .day_step
+                .is_none_or(|step| (time.day() - 1).is_multiple_of(step))
+            && self.last_days.is_none_or(|offset| {
+                days_in_month(time).is_some_and(|length| time.day() + offset as u32 >= length)
+            })
+            && matches(&self.month, time.month())
+            && self
+                .year
+                .as_ref()
+                .is_none_or(|years| years.contains(&time.year()))
+            && self
+                .resolved_days(time)
+                .iter()
+                .all(|&day| day == Some(time.day()))
+    }
+
+    // Whether the day-of-month and day-of-week lists match `time`'s date
+    fn days_match(&self, time: DateTime<Utc>) -> bool {
+        let day = self
+            .day
+            .as_ref()
+            .is_none_or(|days| days.contains(&time.day()));
+        let weekday = self
+            .weekday
+            .as_ref()
+            .is_none_or(|weekdays| weekdays.contains(&time.weekday().num_days_from_monday()));
+        if self.day_or_weekday {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    // Next minute matching the fields on the schedule's wall clock
+    fn next_matching_minute(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self.wall_clock {
+            WallClock::Utc => self.next_occurrence_with_steps(after).0,
+            WallClock::Local => self.next_occurrence_in_zone(after, &chrono::Local),
+            #[cfg(feature = "chrono-tz")]
+            WallClock::Named(tz) => self.next_occurrence_in_zone(after, &tz),
+        }
+    }
+
+    // Next instant whose wall-clock time in `tz` matches the fields
+    pub(crate) fn next_occurrence_in_zone<Tz: TimeZone>(
+        &self,
+        after: DateTime<Utc>,
+        tz: &Tz,
+    ) -> Option<DateTime<Utc>> {
+        next_on_wall_clock(after, tz, self.dst_policy, |wall| {
+            self.next_occurrence_with_steps(wall).0
+        })
+    }
+
+    // Offset into the matched minute, fixed per minute so repeated polls agree
+    fn jitter_for(&self, minute: DateTime<Utc>, max_seconds: u32) -> Duration {
+        let seed = self.jitter_seed.unwrap_or_default() ^ minute.timestamp() as u64;
+        let secs = ChaCha12Rng::seed_from_u64(seed).random_range(0..=max_seconds);
+        Duration::from_secs(secs as u64)
+    }
+
+    // Next occurrence along with the number of loop iterations needed to find it
+    pub(crate) fn next_occurrence_with_steps(
+        &self,
+        after: DateTime<Utc>,
+    ) -> (Option<DateTime<Utc>>, usize) {
+        let mut steps = 0;
+
+        // An impossible day/month combination would otherwise search forever
+        if !self.is_satisfiable() {
+            return (None, steps);
+        }
+
+        // Start from the next whole minute to ensure we don't get the same time again. Nothing
+        // follows a time in the last representable minute
+        let start = after.with_second(0).unwrap().with_nanosecond(0).unwrap();
+        let Some(mut next) = start.checked_add_signed(chrono::TimeDelta::minutes(1)) else {
+            return (None, steps);
+        };
 
         loop {
+            steps += 1;
+
+            // Check year, giving up once past the last listed one
+            if let Some(years) = &self.year {
+                if !years.contains(&next.year()) {
+                    next = match next_in(years, next.year())
+                        .and_then(|year| start_of_month(year, 1))
+                    {
+                        Some(target) => target,
+                        None => return (None, steps),
+                    };
+                    continue;
+                }
+            } else if next.year() > after.year() + 400 {
+                // The calendar repeats every 400 years, so no later match exists
+                return (None, steps);
+            }
+
             // Check month
-            if let Some(month) = self.month {
-                match next.month().cmp(&month) {
-                    std::cmp::Ordering::Less => {
-                        next = next
-                            .with_month(month)
-                            .unwrap()
-                            .with_day(1)
-                            .unwrap()
-                            .with_hour(0)
-                            .unwrap()
-                            .with_minute(0)
-                            .unwrap()
-                            .with_second(0)
-                            .unwrap();
-                        continue;
+            if let Some(months) = &self.month {
+                if !months.contains(&next.month()) {
+                    // Next listed month this year, or the first one next year
+                    let target = match next_in(months, next.month()) {
+                        Some(month) => start_of_month(next.year(), month),
+                        None => start_of_month(next.year() + 1, months[0]),
+                    };
+                    match target {
+                        Some(target) => {
+                            next = target;
+                            continue;
+                        }
+                        None => return (None, steps),
                     }
-                    std::cmp::Ordering::Greater => {
-                        next = next
-                            .with_year(next.year() + 1)
-                            .unwrap()
-                            .with_month(1)
-                            .unwrap()
-                            .with_day(1)
-                            .unwrap()
-                            .with_hour(0)
-                            .unwrap()
-                            .with_minute(0)
-                            .unwrap()
-                            .with_second(0)
-                            .unwrap();
-                        continue;
-                    }
-                    std::cmp::Ordering::Equal => {}
                 }
             }
 
-            // Check day
-            if let Some(day) = self.day {
-                match next.day().cmp(&day) {
-                    std::cmp::Ordering::Less => {
-                        next = next
-                            .with_day(day)
-                            .unwrap()
-                            .with_hour(0)
-                            .unwrap()
-                            .with_minute(0)
-                            .unwrap()
-                            .with_second(0)
-                            .unwrap();
-                        continue;
+            // Check days resolved per month, eg: 15W or the third Saturday
+            let target = self
+                .resolved_days(next)
+                .into_iter()
+                .find_map(|resolved| match resolved {
+                    Some(day) if day == next.day() => None,
+                    Some(day) if day > next.day() => {
+                        Some(next.date_naive().with_day(day).map(start_of_day))
                     }
-                    std::cmp::Ordering::Greater => {
-                        next = next
-                            .with_month(next.month() + 1)
-                            .unwrap()
-                            .with_day(1)
-                            .unwrap()
-                            .with_hour(0)
-                            .unwrap()
-                            .with_minute(0)
-                            .unwrap()
-                            .with_second(0)
-                            .unwrap();
+                    _ => Some(start_of_next_month(next)),
+                });
+            if let Some(target) = target {
+                match target {
+                    Some(target) => {
+                        next = target;
                         continue;
                     }
-                    std::cmp::Ordering::Equal => {}
+                    None => return (None, steps),
+                }
+            }
+
+            // Check day-of-month or day-of-week, a day at a time
+            if self.day_or_weekday && !self.days_match(next) {
+                next = start_of_day(next.date_naive()) + Duration::from_secs(86400);
+                continue;
+            }
+
+            // Check day
+            if let Some(days) = self.day.as_ref().filter(|_| !self.day_or_weekday) {
+                if !days.contains(&next.day()) {
+                    // Jump straight to the next listed day, or past months too short to have it
+                    let date = next_in(days, next.day())
+                        .and_then(|day| next.date_naive().with_day(day))
+                        .map(start_of_day);
+                    next = match date.or_else(|| start_of_next_month(next)) {
+                        Some(target) => target,
+                        None => return (None, steps),
+                    };
+                    continue;
+                }
+            }
+
+            // Check day step
+            if let Some(step) = self.day_step {
+                let offset = (next.day() - 1) % step;
+                if offset != 0 {
+                    // Jump to the next stepped day, or restart from the 1st of next month
+                    let stepped_day = next.day() - offset + step;
+                    next = match next.date_naive().with_day(stepped_day) {
+                        Some(date) => start_of_day(date),
+                        None => match start_of_next_month(next) {
+                            Some(next_month) => next_month,
+                            None => return (None, steps),
+                        },
+                    };
+                    continue;
+                }
+            }
+
+            // Check last days of the month
+            if let Some(offset) = self.last_days {
+                let Some(length) = days_in_month(next) else {
+                    return (None, steps);
+                };
+                let first_day = length - offset as u32;
+                if next.day() < first_day {
+                    next = start_of_day(next.date_naive().with_day(first_day).unwrap());
+                    continue;
                 }
             }
 
             // Check weekday
-            if let Some(weekday) = self.weekday {
-                if next.weekday().num_days_from_monday() != weekday {
-                    next = next
-                        .with_hour(0)
-                        .unwrap()
-                        .with_minute(0)
-                        .unwrap()
-                        .with_second(0)
-                        .unwrap()
-                        + Duration::from_secs(86400);
+            if let Some(weekdays) = self.weekday.as_ref().filter(|_| !self.day_or_weekday) {
+                let current = next.weekday().num_days_from_monday();
+                if !weekdays.contains(&current) {
+                    // Jump straight to the nearest matching weekday
+                    let days_ahead = weekdays
+                        .iter()
+                        .map(|weekday| (weekday + 7 - current) % 7)
+                        .min()
+                        .unwrap_or_default();
+                    next = match start_of_day(next.date_naive())
+                        .checked_add_signed(chrono::TimeDelta::days(days_ahead as i64))
+                    {
+                        Some(target) => target,
+                        None => return (None, steps),
+                    };
                     continue;
                 }
             }
 
             // Check hour
-            if let Some(hour) = self.hour {
-                match next.hour().cmp(&hour) {
-                    std::cmp::Ordering::Less => {
-                        next = next
-                            .with_hour(hour)
-                            .unwrap()
-                            .with_minute(0)
-                            .unwrap()
-                            .with_second(0)
-                            .unwrap();
-                        continue;
-                    }
-                    std::cmp::Ordering::Greater => {
-                        next = next
-                            .with_day(next.day() + 1)
-                            .unwrap()
-                            .with_hour(0)
-                            .unwrap()
-                            .with_minute(0)
-                            .unwrap()
-                            .with_second(0)
-                            .unwrap();
-                        continue;
+            if let Some(hours) = &self.hour {
+                if !hours.contains(&next.hour()) {
+                    // Next listed hour today, or midnight tomorrow
+                    let target = match next_in(hours, next.hour()) {
+                        Some(hour) => start_of_day(next.date_naive())
+                            .checked_add_signed(chrono::TimeDelta::hours(hour as i64)),
+                        None => start_of_day(next.date_naive())
+                            .checked_add_signed(chrono::TimeDelta::days(1)),
+                    };
+                    match target {
+                        Some(target) => {
+                            next = target;
+                            continue;
+                        }
+                        None => return (None, steps),
                     }
-                    std::cmp::Ordering::Equal => {}
                 }
             }
 
             // Check minute
-            if let Some(minute) = self.minute {
-                match next.minute().cmp(&minute) {
-                    std::cmp::Ordering::Less => {
-                        next = next.with_minute(minute).unwrap().with_second(0).unwrap();
-                        continue;
-                    }
-                    std::cmp::Ordering::Greater => {
-                        next = next
-                            .with_hour(next.hour() + 1)
-                            .unwrap()
+            if let Some(minutes) = &self.minute {
+                if !minutes.contains(&next.minute()) {
+                    let target = match next_in(minutes, next.minute()) {
+                        Some(minute) => next.with_minute(minute).unwrap().with_second(0),
+                        // Move to the top of the next hour, rolling over the day, month and year
+                        None => next
                             .with_minute(0)
                             .unwrap()
                             .with_second(0)
-                            .unwrap();
-                        continue;
+                            .unwrap()
+                            .checked_add_signed(chrono::TimeDelta::hours(1)),
+                    };
+                    match target {
+                        Some(target) => {
+                            next = target;
+                            continue;
+                        }
+                        None => return (None, steps),
                     }
-                    std::cmp::Ordering::Equal => {}
                 }
             }
 
             // If we get here, all conditions are satisfied
-            return Some(next);
+            return (Some(next), steps);
         }
     }
 }
@@ -0,0 +1,23 @@
+use super::*;
+
+// Schedule backed by a user supplied event function, eg: tides or moon phases
+pub struct CustomEventSchedule {
+    next_event: Box<dyn Fn(DateTime<Utc>) -> Option<DateTime<Utc>>>,
+}
+
+impl CustomEventSchedule {
+    pub fn new(next_event: Box<dyn Fn(DateTime<Utc>) -> Option<DateTime<Utc>>>) -> Self {
+        Self { next_event }
+    }
+}
+
+impl Schedule for CustomEventSchedule {
+    fn describe(&self) -> String {
+        String::from("custom events")
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        // Ignore events that don't move forward in time
+        (self.next_event)(after).filter(|next| *next > after)
+    }
+}
@@ -0,0 +1,54 @@
+use super::*;
+
+// Fixed times of day, every day, eg: 09:00, 13:30 and 17:45
+pub struct DailyTimesSchedule {
+    times: Vec<NaiveTime>,
+}
+
+impl DailyTimesSchedule {
+    pub fn new(mut times: Vec<NaiveTime>) -> Result<Self, SchedulerError> {
+        if times.is_empty() {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+        times.sort();
+        times.dedup();
+        Ok(Self { times })
+    }
+
+    // Parse a comma separated list of HH:MM times
+    pub fn parse(times: &str) -> Result<Self, SchedulerError> {
+        let times = times
+            .split(',')
+            .map(|token| {
+                NaiveTime::parse_from_str(token.trim(), "%H:%M")
+                    .map_err(|_| SchedulerError::InvalidDateTime)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(times)
+    }
+}
+
+impl Schedule for DailyTimesSchedule {
+    fn describe(&self) -> String {
+        let times: Vec<String> = self
+            .times
+            .iter()
+            .map(|time| time.format("%H:%M").to_string())
+            .collect();
+        format!("daily at {}", times.join(", "))
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let today = after.date_naive();
+        let tomorrow = today.succ_opt()?;
+
+        [today, tomorrow]
+            .into_iter()
+            .flat_map(|date| {
+                self.times
+                    .iter()
+                    .map(move |time| date.and_time(*time).and_utc())
+            })
+            .find(|next| *next > after)
+    }
+}
@@ -0,0 +1,89 @@
+use super::*;
+
+// Schedule skipping occurrences that fall inside blackout windows
+pub struct ExceptSchedule {
+    inner: Box<dyn Schedule>,
+    // Sorted, non-overlapping [start, end) windows
+    windows: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+}
+
+impl ExceptSchedule {
+    pub fn new(
+        inner: Box<dyn Schedule>,
+        mut windows: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+    ) -> Result<Self, SchedulerError> {
+        if windows.iter().any(|(start, end)| start >= end) {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+
+        // Coalesce overlapping and adjacent windows so each skip clears a whole blackout
+        windows.sort();
+        let mut merged: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::with_capacity(windows.len());
+        for (start, end) in windows {
+            match merged.last_mut() {
+                Some((_, last_end)) if start <= *last_end => *last_end = (*last_end).max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+
+        Ok(Self {
+            inner,
+            windows: merged,
+        })
+    }
+
+    pub fn windows(&self) -> &[(DateTime<Utc>, DateTime<Utc>)] {
+        &self.windows
+    }
+
+    fn blackout_containing(&self, time: DateTime<Utc>) -> Option<&(DateTime<Utc>, DateTime<Utc>)> {
+        self.windows
+            .iter()
+            .find(|(start, end)| *start <= time && time < *end)
+    }
+}
+
+impl Schedule for ExceptSchedule {
+    fn is_satisfiable(&self) -> bool {
+        self.inner.is_satisfiable()
+    }
+
+    fn validate(&self) -> Result<(), SchedulerError> {
+        self.inner.validate()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} except {} blackout window(s)",
+            self.inner.describe(),
+            self.windows.len()
+        )
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut probe = after;
+
+        loop {
+            let next = self.inner.next_occurrence(probe)?;
+            let Some((_, end)) = self.blackout_containing(next) else {
+                return Some(next);
+            };
+
+            // Resume just before the window closes so an occurrence at its end still counts,
+            // without skipping one in its last second
+            let resume = *end - chrono::TimeDelta::nanoseconds(1);
+            if resume <= probe {
+                return None;
+            }
+            probe = resume;
+        }
+    }
+
+    fn record_fire(&self, time: DateTime<Utc>) {
+        self.inner.record_fire(time);
+    }
+
+    fn remaining_occurrences(&self) -> Option<u32> {
+        self.inner.remaining_occurrences()
+    }
+}
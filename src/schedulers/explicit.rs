@@ -0,0 +1,42 @@
+use super::*;
+
+// Fixed list of instants, eg: a hand-picked set of release windows
+pub struct ExplicitSchedule {
+    times: Vec<DateTime<Utc>>,
+}
+
+impl ExplicitSchedule {
+    pub fn new(mut times: Vec<DateTime<Utc>>) -> Result<Self, SchedulerError> {
+        if times.is_empty() {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+        times.sort();
+        times.dedup();
+        Ok(Self { times })
+    }
+
+    pub fn times(&self) -> &[DateTime<Utc>] {
+        &self.times
+    }
+}
+
+impl Schedule for ExplicitSchedule {
+    fn describe(&self) -> String {
+        format!("at {} fixed times", self.times.len())
+    }
+
+    fn span(&self) -> Option<Duration> {
+        let (first, last) = (self.times.first()?, self.times.last()?);
+        (*last - *first).to_std().ok()
+    }
+
+    fn previous_occurrence(&self, before: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let index = self.times.partition_point(|time| *time < before);
+        index.checked_sub(1).map(|index| self.times[index])
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let index = self.times.partition_point(|time| *time <= after);
+        self.times.get(index).copied()
+    }
+}
@@ -0,0 +1,73 @@
+use super::*;
+
+// Interval schedule that only fires on a set of weekdays, eg: every 2 hours on weekdays
+pub struct FilteredIntervalSchedule {
+    interval: Duration,
+    start_time: DateTime<Utc>,
+    weekdays: Vec<Weekday>,
+}
+
+impl FilteredIntervalSchedule {
+    pub fn new(
+        interval: Duration,
+        start_time: DateTime<Utc>,
+        weekdays: Vec<Weekday>,
+    ) -> Result<Self, SchedulerError> {
+        if interval.as_secs() == 0 {
+            return Err(SchedulerError::InvalidDuration);
+        }
+        if weekdays.is_empty() {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+
+        Ok(Self {
+            interval,
+            start_time,
+            weekdays,
+        })
+    }
+
+    // First grid point strictly after `after`
+    fn grid_point_after(&self, after: DateTime<Utc>) -> DateTime<Utc> {
+        if after < self.start_time {
+            return self.start_time;
+        }
+        let interval_millis = self.interval.as_millis() as i64;
+        let intervals_passed = (after - self.start_time).num_milliseconds() / interval_millis;
+        self.start_time + chrono::TimeDelta::milliseconds(interval_millis * (intervals_passed + 1))
+    }
+}
+
+impl Schedule for FilteredIntervalSchedule {
+    fn describe(&self) -> String {
+        let weekdays: Vec<String> = self.weekdays.iter().map(|day| day.to_string()).collect();
+        format!(
+            "every {} from {} on {}",
+            format_duration(self.interval),
+            self.start_time,
+            weekdays.join(", ")
+        )
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut next = self.grid_point_after(after);
+
+        // Give up once a whole week of grid points has been skipped
+        let give_up_at = next + self.interval * 7 + chrono::TimeDelta::weeks(1);
+
+        while !self.weekdays.contains(&next.weekday()) {
+            // Skip the rest of a disallowed day in one go
+            let next_day = next
+                .date_naive()
+                .succ_opt()?
+                .and_time(NaiveTime::MIN)
+                .and_utc();
+            next = self.grid_point_after(next_day - chrono::TimeDelta::nanoseconds(1));
+            if next > give_up_at {
+                return None;
+            }
+        }
+
+        Some(next)
+    }
+}
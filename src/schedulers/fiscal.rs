@@ -0,0 +1,134 @@
+use super::*;
+
+// Fiscal quarter schedule, with quarters counted from a configurable fiscal-year start month
+pub struct FiscalSchedule {
+    start_month: u32,
+    quarter: Option<u32>,
+    day_of_quarter: u32,
+    // Count only Monday to Friday towards the day of the quarter
+    business_days: bool,
+    time: NaiveTime,
+}
+
+impl FiscalSchedule {
+    // Fires on the first day of every fiscal quarter at midnight until narrowed down
+    pub fn new(start_month: u32) -> Result<Self, SchedulerError> {
+        if start_month == 0 || start_month > 12 {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+        Ok(Self {
+            start_month,
+            quarter: None,
+            day_of_quarter: 1,
+            business_days: false,
+            time: NaiveTime::MIN,
+        })
+    }
+
+    // Calendar quarters starting January, April, July and October
+    pub fn calendar_quarters() -> Self {
+        Self::new(1).expect("January is a valid start month")
+    }
+
+    pub fn quarter(mut self, quarter: u32) -> Result<Self, SchedulerError> {
+        if quarter == 0 || quarter > 4 {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+        self.quarter = Some(quarter);
+        Ok(self)
+    }
+
+    // Day counted from the start of the quarter, so 1 is the quarter's first day
+    pub fn day_of_quarter(mut self, day: u32) -> Result<Self, SchedulerError> {
+        if day == 0 || day > 92 {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+        self.day_of_quarter = day;
+        self.business_days = false;
+        Ok(self)
+    }
+
+    // Nth Monday to Friday of the quarter, so 1 is the quarter's first business day
+    pub fn business_day_of_quarter(mut self, day: u32) -> Result<Self, SchedulerError> {
+        // No quarter has more than 66 weekdays
+        if day == 0 || day > 66 {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+        self.day_of_quarter = day;
+        self.business_days = true;
+        Ok(self)
+    }
+
+    pub fn at(mut self, hour: u32, minute: u32) -> Result<Self, SchedulerError> {
+        self.time =
+            NaiveTime::from_hms_opt(hour, minute, 0).ok_or(SchedulerError::InvalidDateTime)?;
+        Ok(self)
+    }
+
+    // Fiscal quarter (1-4) beginning in the given calendar month, if one does
+    fn quarter_starting_in(&self, month: u32) -> Option<u32> {
+        let months_into_year = (month + 12 - self.start_month) % 12;
+        months_into_year
+            .is_multiple_of(3)
+            .then_some(months_into_year / 3 + 1)
+    }
+
+    // Occurrence within the quarter starting on `quarter_start`, unless the day runs past its end
+    fn occurrence_in_quarter(&self, quarter_start: NaiveDate) -> Option<DateTime<Utc>> {
+        let date = if self.business_days {
+            quarter_start
+                .iter_days()
+                .filter(|date| !is_weekend(*date))
+                .nth(self.day_of_quarter as usize - 1)?
+        } else {
+            quarter_start + chrono::Days::new(self.day_of_quarter as u64 - 1)
+        };
+        let quarter_end = quarter_start.checked_add_months(chrono::Months::new(3))?;
+        (date < quarter_end).then(|| date.and_time(self.time).and_utc())
+    }
+}
+
+impl Schedule for FiscalSchedule {
+    fn describe(&self) -> String {
+        let quarter = self
+            .quarter
+            .map_or(String::from("every quarter"), |quarter| {
+                format!("Q{quarter}")
+            });
+        let day = if self.business_days {
+            "business day"
+        } else {
+            "day"
+        };
+        format!(
+            "fiscal {} {} {} at {} (year starting month {})",
+            quarter,
+            day,
+            self.day_of_quarter,
+            self.time.format("%H:%M"),
+            self.start_month
+        )
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        // Start from the quarter that could still contain `after`
+        let mut month_start = NaiveDate::from_ymd_opt(after.year(), after.month(), 1)?
+            .checked_sub_months(chrono::Months::new(3))?;
+
+        // A late day may only fit the quarter in leap years, or with the right weekdays, and the
+        // calendar repeats every 400 years
+        for _ in 0..12 * 400 + 3 {
+            let quarter = self.quarter_starting_in(month_start.month());
+            if quarter.is_some() && (self.quarter.is_none() || self.quarter == quarter) {
+                if let Some(next) = self.occurrence_in_quarter(month_start) {
+                    if next > after {
+                        return Some(next);
+                    }
+                }
+            }
+            month_start = month_start.checked_add_months(chrono::Months::new(1))?;
+        }
+
+        None
+    }
+}
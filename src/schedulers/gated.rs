@@ -0,0 +1,47 @@
+use super::*;
+
+// Schedule that only fires while an external flag is enabled
+pub struct GatedSchedule {
+    inner: Box<dyn Schedule>,
+    enabled: Box<dyn Fn() -> bool>,
+}
+
+impl GatedSchedule {
+    pub fn new(inner: Box<dyn Schedule>, enabled: Box<dyn Fn() -> bool>) -> Self {
+        Self { inner, enabled }
+    }
+}
+
+impl Schedule for GatedSchedule {
+    fn is_satisfiable(&self) -> bool {
+        self.inner.is_satisfiable()
+    }
+
+    fn validate(&self) -> Result<(), SchedulerError> {
+        self.inner.validate()
+    }
+
+    fn describe(&self) -> String {
+        format!("{} while enabled", self.inner.describe())
+    }
+
+    fn is_finished(&self, after: DateTime<Utc>) -> bool {
+        self.inner.is_finished(after)
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        // The flag is consulted on every call, so fires resume as soon as it flips back
+        if !(self.enabled)() {
+            return None;
+        }
+        self.inner.next_occurrence(after)
+    }
+
+    fn record_fire(&self, time: DateTime<Utc>) {
+        self.inner.record_fire(time);
+    }
+
+    fn remaining_occurrences(&self) -> Option<u32> {
+        self.inner.remaining_occurrences()
+    }
+}
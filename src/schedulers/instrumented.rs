@@ -0,0 +1,97 @@
+use super::*;
+use std::{collections::BTreeSet, rc::Rc};
+
+// Occurrences an instrumented schedule handed to the filters wrapping it, and how many of those
+// the filters dropped rather than fired. Both settle as fires go out: everything handed over
+// before a fire either is that fire or was dropped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SuppressionCounts {
+    pub emitted: u64,
+    pub suppressed: u64,
+}
+
+// Shared handle to an instrumented schedule's counts, still readable once the schedule is
+// wrapped in filters and moved into a job
+#[derive(Clone, Default)]
+pub struct SuppressionCounter(Rc<Cell<SuppressionCounts>>);
+
+impl SuppressionCounter {
+    pub fn counts(&self) -> SuppressionCounts {
+        self.0.get()
+    }
+}
+
+// Schedule counting the occurrences it emits towards filters wrapped around it, eg: blackout
+// windows, a feature flag or quantizing, and those the filters suppress, to help tune them
+pub struct InstrumentedSchedule {
+    inner: Box<dyn Schedule>,
+    counter: SuppressionCounter,
+    // Occurrences handed out that no fire has settled yet
+    pending: RefCell<BTreeSet<DateTime<Utc>>>,
+}
+
+impl InstrumentedSchedule {
+    pub fn new(inner: Box<dyn Schedule>) -> Self {
+        Self {
+            inner,
+            counter: SuppressionCounter::default(),
+            pending: RefCell::new(BTreeSet::new()),
+        }
+    }
+
+    pub fn counter(&self) -> SuppressionCounter {
+        self.counter.clone()
+    }
+}
+
+impl Schedule for InstrumentedSchedule {
+    fn is_satisfiable(&self) -> bool {
+        self.inner.is_satisfiable()
+    }
+
+    fn validate(&self) -> Result<(), SchedulerError> {
+        self.inner.validate()
+    }
+
+    fn describe(&self) -> String {
+        self.inner.describe()
+    }
+
+    fn is_finished(&self, after: DateTime<Utc>) -> bool {
+        self.inner.is_finished(after)
+    }
+
+    // Looking back isn't an emitted occurrence, so nothing is counted
+    fn previous_occurrence(&self, before: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.inner.previous_occurrence(before)
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let next = self.inner.next_occurrence(after);
+
+        // Nothing falls between `after` and `next`, so anything handed out in between was a
+        // random draw since replaced
+        let mut pending = self.pending.borrow_mut();
+        pending.retain(|seen| *seen <= after || next.is_some_and(|next| *seen >= next));
+        pending.extend(next);
+        next
+    }
+
+    fn record_fire(&self, time: DateTime<Utc>) {
+        self.inner.record_fire(time);
+
+        let mut pending = self.pending.borrow_mut();
+        let later = pending.split_off(&(time + chrono::TimeDelta::nanoseconds(1)));
+        let settled = std::mem::replace(&mut *pending, later);
+        let suppressed = settled.range(..time).count() as u64;
+
+        let mut counts = self.counter.counts();
+        counts.emitted += suppressed + 1;
+        counts.suppressed += suppressed;
+        self.counter.0.set(counts);
+    }
+
+    fn remaining_occurrences(&self) -> Option<u32> {
+        self.inner.remaining_occurrences()
+    }
+}
@@ -1,10 +1,23 @@
 use super::*;
 
+// Whether the start time itself is an interval schedule's first occurrence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntervalStart {
+    // Fire at the start time, then every interval after it
+    #[default]
+    IncludeStart,
+    // Wait a full interval after the start time before the first fire
+    ExcludeStart,
+}
+
 // Interval schedule
 pub struct IntervalSchedule {
     interval: Duration,
     start_time: DateTime<Utc>,
     end_time: Option<DateTime<Utc>>,
+    start: IntervalStart,
+    wall_clock: WallClock,
+    dst_policy: DstPolicy,
 }
 
 impl IntervalSchedule {
@@ -17,25 +30,185 @@ impl IntervalSchedule {
             interval,
             start_time,
             end_time: None,
+            start: IntervalStart::default(),
+            wall_clock: WallClock::default(),
+            dst_policy: DstPolicy::default(),
         })
     }
 
+    // `count` evenly spaced occurrences per `period`, eg: 4 times a day from midnight
+    pub fn evenly_within(
+        period: Duration,
+        count: u32,
+        anchor: DateTime<Utc>,
+    ) -> Result<Self, SchedulerError> {
+        if count == 0 {
+            return Err(SchedulerError::InvalidRepetition);
+        }
+        Self::new(period / count, anchor)
+    }
+
     pub fn with_end_time(mut self, end_time: DateTime<Utc>) -> Self {
         self.end_time = Some(end_time);
         self
     }
+
+    // Either way, occurrences stay on the grid anchored at the start time
+    pub fn with_start(mut self, start: IntervalStart) -> Self {
+        self.start = start;
+        self
+    }
+
+    // Lay the grid out on the machine's wall clock, read each time an occurrence is computed
+    pub fn with_local_timezone(mut self) -> Self {
+        self.wall_clock = WallClock::Local;
+        self
+    }
+
+    // Lay the grid out on the wall clock in a named zone, so a daily interval keeps its
+    // local time of day across DST changes
+    #[cfg(feature = "chrono-tz")]
+    pub fn with_timezone(mut self, tz: chrono_tz::Tz) -> Self {
+        self.wall_clock = WallClock::Named(tz);
+        self
+    }
+
+    // Choose how a local-time grid treats times a DST change skips or repeats
+    pub fn with_dst_policy(mut self, policy: DstPolicy) -> Self {
+        self.dst_policy = policy;
+        self
+    }
+
+    // Earliest grid point that counts as an occurrence
+    fn first_time(&self) -> DateTime<Utc> {
+        self.first_time_from(self.start_time)
+    }
+
+    fn first_time_from(&self, start_time: DateTime<Utc>) -> DateTime<Utc> {
+        match self.start {
+            IntervalStart::IncludeStart => start_time,
+            IntervalStart::ExcludeStart => start_time + self.interval,
+        }
+    }
+
+    // Next point after `after` on the grid anchored at `start_time`
+    fn next_grid_point(&self, start_time: DateTime<Utc>, after: DateTime<Utc>) -> DateTime<Utc> {
+        if after < self.first_time_from(start_time) {
+            return self.first_time_from(start_time);
+        }
+
+        let since_start = after - start_time;
+        let intervals_passed =
+            since_start.num_milliseconds() as u64 / self.interval.as_millis() as u64;
+        start_time + self.interval * (intervals_passed + 1) as u32
+    }
+
+    pub fn metrics_snapshot(&self, now: DateTime<Utc>) -> MetricsSnapshot {
+        let next = self.next_occurrence(now);
+
+        // Every grid point from the start up to now (or the end time) has fired
+        let last_counted = self.end_time.map_or(now, |end| now.min(end));
+        let total_fires = if last_counted < self.first_time() {
+            0
+        } else {
+            (last_counted - self.first_time()).num_milliseconds() as u64
+                / self.interval.as_millis() as u64
+                + 1
+        };
+
+        MetricsSnapshot {
+            seconds_until_next: next.map(|next| (next - now).num_seconds()),
+            total_fires,
+            finished: next.is_none(),
+        }
+    }
 }
 
 impl Schedule for IntervalSchedule {
-    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
-        if after < self.start_time {
-            return Some(self.start_time);
+    fn matches(&self, time: DateTime<Utc>, tolerance: Duration) -> bool {
+        if self.wall_clock != WallClock::Utc {
+            return occurs_within(self, time, tolerance);
         }
+        let tolerance_ms = tolerance.as_millis() as i64;
+        let interval_ms = self.interval.as_millis() as i64;
+        let since_start_ms = (time - self.start_time).num_milliseconds();
 
-        let since_start = after - self.start_time;
-        let intervals_passed =
-            (since_start.as_seconds_f32() / self.interval.as_secs() as f32) as u64;
-        let next_time = self.start_time + self.interval * (intervals_passed + 1) as u32;
+        // Nearest grid point, never before the first occurrence
+        let nearest = if since_start_ms <= 0 {
+            self.first_time()
+        } else {
+            let intervals = (since_start_ms + interval_ms / 2) / interval_ms;
+            self.first_time()
+                .max(self.start_time + self.interval * intervals as u32)
+        };
+
+        let within_end = self.end_time.is_none_or(|end| nearest <= end);
+        within_end && (time - nearest).num_milliseconds().abs() <= tolerance_ms
+    }
+
+    fn describe(&self) -> String {
+        let mut description = format!(
+            "every {} from {}",
+            format_duration(self.interval),
+            self.start_time
+        );
+        if self.start == IntervalStart::ExcludeStart {
+            description.push_str(" (excluding start)");
+        }
+        description.push_str(&self.wall_clock.describe());
+        if let Some(end) = self.end_time {
+            description.push_str(&format!(" until {end}"));
+        }
+        description
+    }
+
+    fn span(&self) -> Option<Duration> {
+        let end = self.end_time?;
+        if end < self.first_time() {
+            return None;
+        }
+
+        // Last grid point at or before the end time
+        let fires_after_first =
+            (end - self.first_time()).num_milliseconds() as u64 / self.interval.as_millis() as u64;
+        Some(self.interval * fires_after_first as u32)
+    }
+
+    fn is_satisfiable(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    // An end time before the first fire is a misconfiguration
+    fn validate(&self) -> Result<(), SchedulerError> {
+        match self.end_time {
+            Some(end) if end < self.first_time() => Err(SchedulerError::InvalidConfiguration),
+            _ => Ok(()),
+        }
+    }
+
+    fn previous_occurrence(&self, before: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.wall_clock != WallClock::Utc {
+            return latest_before(self, before);
+        }
+
+        // Last grid point before `before`, never past the end time
+        let bound = self.end_time.map_or(before, |end| {
+            before.min(end + chrono::TimeDelta::nanoseconds(1))
+        });
+        if bound <= self.first_time() {
+            return None;
+        }
+        let since_start = bound - self.start_time - chrono::TimeDelta::nanoseconds(1);
+        let intervals = since_start.num_milliseconds() as u64 / self.interval.as_millis() as u64;
+        Some(self.start_time + self.interval * intervals as u32)
+            .filter(|&previous| previous >= self.first_time())
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start_time = self.wall_clock.wall_time(self.start_time);
+        let next_time = self.wall_clock.next_after(after, self.dst_policy, |wall| {
+            Some(self.next_grid_point(start_time, wall))
+        })?;
 
         match self.end_time {
             Some(end) if next_time > end => None,
@@ -0,0 +1,119 @@
+use super::*;
+
+// Override layered over the base schedule for a [start, end) window
+struct Layer {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    schedule: Box<dyn Schedule>,
+}
+
+// Base schedule whose cadence is replaced by override layers inside their windows,
+// eg: hourly normally but every 5 minutes during an incident
+pub struct LayeredSchedule {
+    base: Box<dyn Schedule>,
+    // Later layers take priority where windows overlap
+    layers: Vec<Layer>,
+}
+
+impl LayeredSchedule {
+    pub fn new(base: Box<dyn Schedule>) -> Self {
+        Self {
+            base,
+            layers: Vec::new(),
+        }
+    }
+
+    pub fn layer(
+        mut self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        schedule: Box<dyn Schedule>,
+    ) -> Result<Self, SchedulerError> {
+        if start >= end {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+        self.layers.push(Layer {
+            start,
+            end,
+            schedule,
+        });
+        Ok(self)
+    }
+
+    // Schedule in charge at `time` and for as long as no window opens or closes
+    fn active_at(&self, time: DateTime<Utc>) -> &dyn Schedule {
+        self.layers
+            .iter()
+            .rev()
+            .find(|layer| layer.start <= time && time < layer.end)
+            .map_or(self.base.as_ref(), |layer| layer.schedule.as_ref())
+    }
+
+    // Earliest window start or end strictly after `time`
+    fn next_boundary(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.layers
+            .iter()
+            .flat_map(|layer| [layer.start, layer.end])
+            .filter(|boundary| *boundary > time)
+            .min()
+    }
+}
+
+impl Schedule for LayeredSchedule {
+    fn is_satisfiable(&self) -> bool {
+        self.base.is_satisfiable()
+            || self
+                .layers
+                .iter()
+                .any(|layer| layer.schedule.is_satisfiable())
+    }
+
+    // The base schedule's error when no layer can fire either
+    fn validate(&self) -> Result<(), SchedulerError> {
+        if self.is_satisfiable() {
+            return Ok(());
+        }
+        self.base.validate()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} with {} override layer(s)",
+            self.base.describe(),
+            self.layers.len()
+        )
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        // Walk the segments between window boundaries, each owned by a single schedule
+        let mut segment_start = after;
+
+        loop {
+            let active = self.active_at(segment_start);
+            let segment_end = self.next_boundary(segment_start);
+
+            // A later segment may fire right on its boundary, so look from just before it
+            let mut query = if segment_start == after {
+                after
+            } else {
+                segment_start - chrono::TimeDelta::seconds(1)
+            };
+            let candidate = loop {
+                match active.next_occurrence(query) {
+                    Some(next) if next < segment_start && next > query => query = next,
+                    Some(next) if next >= segment_start && next > after => break Some(next),
+                    _ => break None,
+                }
+            };
+
+            match candidate {
+                Some(next) if segment_end.is_none_or(|end| next < end) => return Some(next),
+                _ => segment_start = segment_end?,
+            }
+        }
+    }
+
+    fn record_fire(&self, time: DateTime<Utc>) {
+        self.active_at(time).record_fire(time);
+    }
+}
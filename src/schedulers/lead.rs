@@ -0,0 +1,46 @@
+use super::*;
+
+// Schedule firing a fixed duration ahead of another schedule's occurrences
+pub struct LeadSchedule {
+    inner: Box<dyn Schedule>,
+    lead: Duration,
+}
+
+impl LeadSchedule {
+    pub fn new(inner: Box<dyn Schedule>, lead: Duration) -> Self {
+        Self { inner, lead }
+    }
+}
+
+impl Schedule for LeadSchedule {
+    fn is_satisfiable(&self) -> bool {
+        self.inner.is_satisfiable()
+    }
+
+    fn validate(&self) -> Result<(), SchedulerError> {
+        self.inner.validate()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} before {}",
+            format_duration(self.lead),
+            self.inner.describe()
+        )
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        // An inner occurrence at `after + lead` would fire exactly at `after`, so skip past it
+        self.inner
+            .next_occurrence(after + self.lead)
+            .map(|next| next - self.lead)
+    }
+
+    fn record_fire(&self, time: DateTime<Utc>) {
+        self.inner.record_fire(time + self.lead);
+    }
+
+    fn remaining_occurrences(&self) -> Option<u32> {
+        self.inner.remaining_occurrences()
+    }
+}
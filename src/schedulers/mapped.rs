@@ -0,0 +1,53 @@
+use super::*;
+
+// Schedule applying an arbitrary adjustment to each of another schedule's occurrences
+pub struct MappedSchedule {
+    inner: Box<dyn Schedule>,
+    map: Box<dyn Fn(DateTime<Utc>) -> DateTime<Utc>>,
+}
+
+impl MappedSchedule {
+    pub fn new(inner: Box<dyn Schedule>, map: Box<dyn Fn(DateTime<Utc>) -> DateTime<Utc>>) -> Self {
+        Self { inner, map }
+    }
+}
+
+impl Schedule for MappedSchedule {
+    fn is_satisfiable(&self) -> bool {
+        self.inner.is_satisfiable()
+    }
+
+    fn validate(&self) -> Result<(), SchedulerError> {
+        self.inner.validate()
+    }
+
+    fn describe(&self) -> String {
+        format!("{} (adjusted)", self.inner.describe())
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut probe = after;
+
+        // Keep advancing the inner schedule until an adjusted time lands after `after`
+        loop {
+            let next = self.inner.next_occurrence(probe)?;
+            let mapped = (self.map)(next);
+            if mapped > after {
+                return Some(mapped);
+            }
+            if next <= probe {
+                return None;
+            }
+            probe = next;
+        }
+    }
+
+    // The adjustment can't be undone, so the inner schedule hears of the adjusted time
+    fn record_fire(&self, time: DateTime<Utc>) {
+        self.inner.record_fire(time);
+    }
+
+    fn remaining_occurrences(&self) -> Option<u32> {
+        self.inner.remaining_occurrences()
+    }
+}
@@ -1,14 +1,648 @@
 use super::*;
 
-pub use self::{combined::*, cron::*, interval::*, one_time::*, random_interval::*};
+#[cfg(any(test, feature = "test-util"))]
+pub use self::recording::*;
+pub use self::{
+    approaching::*, business_day::*, business_hours::*, calendar::*, capped_horizon::*, clamped::*,
+    combined::*, cron::*, custom_event::*, daily_times::*, except::*, explicit::*,
+    filtered_interval::*, fiscal::*, gated::*, instrumented::*, interval::*, layered::*, lead::*,
+    mapped::*, monthly::*, nth_business_day::*, nth_weekday::*, one_time::*, quantize::*,
+    random_interval::*, random_window::*, repeat_n::*, slack::*, trading::*, weekly::*,
+};
 
+mod approaching;
+mod business_day;
+mod business_hours;
+mod calendar;
+mod capped_horizon;
+mod clamped;
 mod combined;
 mod cron;
+mod custom_event;
+mod daily_times;
+mod except;
+mod explicit;
+mod filtered_interval;
+mod fiscal;
+mod gated;
+mod instrumented;
 mod interval;
+mod layered;
+mod lead;
+mod mapped;
+mod monthly;
+mod nth_business_day;
+mod nth_weekday;
+mod on_calendar;
 mod one_time;
+mod quantize;
 mod random_interval;
+mod random_window;
+#[cfg(any(test, feature = "test-util"))]
+mod recording;
+mod repeat_n;
+mod slack;
+mod trading;
+mod weekly;
 
 // Schedule Trait
 pub trait Schedule {
     fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>>;
+
+    // Latest occurrence strictly before `before`, eg: to tell whether a run was missed while down
+    fn previous_occurrence(&self, before: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        latest_before(self, before)
+    }
+
+    // First occurrence from a fixed reference, reproducible for seeded schedules
+    fn first_occurrence(&self, from: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.next_occurrence(from)
+    }
+
+    // Next occurrence converted to `tz` for display
+    fn next_occurrence_in<Tz: TimeZone>(
+        &self,
+        after: DateTime<Utc>,
+        tz: &Tz,
+    ) -> Option<DateTime<Tz>>
+    where
+        Self: Sized,
+    {
+        self.next_occurrence(after)
+            .map(|next| next.with_timezone(tz))
+    }
+
+    // Whether `time` is an occurrence, give or take `tolerance`
+    fn matches(&self, time: DateTime<Utc>, tolerance: Duration) -> bool {
+        occurs_within(self, time, tolerance)
+    }
+
+    // Up to `max_count` occurrences after `after`, never going past `until`
+    fn occurrences_limited(
+        &self,
+        after: DateTime<Utc>,
+        max_count: usize,
+        until: DateTime<Utc>,
+    ) -> Vec<DateTime<Utc>> {
+        let mut occurrences = Vec::new();
+        let mut previous = after;
+        let mut remaining = self.remaining_occurrences();
+
+        while occurrences.len() < max_count && take_remaining(&mut remaining) {
+            match self.next_occurrence(previous) {
+                Some(next) if next <= until && next > previous => {
+                    occurrences.push(next);
+                    previous = next;
+                }
+                _ => break,
+            }
+        }
+
+        occurrences
+    }
+
+    // Every occurrence from `start` up to but excluding `end`, eg: for capacity planning
+    fn occurrences_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let mut occurrences = Vec::new();
+        let mut previous = start - chrono::TimeDelta::seconds(1);
+        let mut remaining = self.remaining_occurrences();
+
+        while let Some(next) = self.next_occurrence(previous) {
+            if next >= end || next <= previous || !take_remaining(&mut remaining) {
+                break;
+            }
+            if next >= start {
+                occurrences.push(next);
+            }
+            previous = next;
+        }
+
+        occurrences
+    }
+
+    // Upcoming occurrences from `start` onwards, eg: `schedule.iter_from(now).take(10)`
+    fn iter_from(&self, start: DateTime<Utc>) -> Occurrences<'_>
+    where
+        Self: Sized,
+    {
+        Occurrences::new(self, start)
+    }
+
+    // Next `k` occurrences, each paired with the gap since the previous one (or `after`)
+    fn preview(&self, after: DateTime<Utc>, k: usize) -> Vec<(DateTime<Utc>, Duration)> {
+        let mut preview = Vec::with_capacity(k);
+        let mut previous = after;
+        let mut remaining = self.remaining_occurrences();
+
+        while preview.len() < k && take_remaining(&mut remaining) {
+            match self.next_occurrence(previous) {
+                Some(next) if next > previous => {
+                    let gap = (next - previous).to_std().unwrap_or_default();
+                    preview.push((next, gap));
+                    previous = next;
+                }
+                _ => break,
+            }
+        }
+
+        preview
+    }
+
+    // Largest gap between consecutive occurrences in the window, counting the lead-in from `start`
+    fn max_gap(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Option<Duration> {
+        let mut previous = start;
+        let mut max_gap = None;
+        let mut remaining = self.remaining_occurrences();
+
+        while let Some(next) = self.next_occurrence(previous) {
+            if next > end || next <= previous || !take_remaining(&mut remaining) {
+                break;
+            }
+            let gap = (next - previous).to_std().ok()?;
+            max_gap = max_gap.max(Some(gap));
+            previous = next;
+        }
+
+        max_gap
+    }
+
+    // Time from the first to the last occurrence, or None if the schedule is unbounded
+    fn span(&self) -> Option<Duration> {
+        None
+    }
+
+    // Whether any occurrence falls within `horizon` of `now`, to flag effectively dead schedules
+    fn fires_within(&self, now: DateTime<Utc>, horizon: Duration) -> bool {
+        self.is_satisfiable()
+            && self
+                .next_occurrence(now)
+                .is_some_and(|next| next <= now + horizon)
+    }
+
+    // Whether the schedule can ever produce an occurrence
+    fn is_satisfiable(&self) -> bool {
+        true
+    }
+
+    // Why the schedule can never produce an occurrence, eg: a misconfigured end time, or
+    // Unsatisfiable when there's nothing more specific to report
+    fn validate(&self) -> Result<(), SchedulerError> {
+        if self.is_satisfiable() {
+            Ok(())
+        } else {
+            Err(SchedulerError::Unsatisfiable)
+        }
+    }
+
+    // Whether nothing will fire after `after` ever again, rather than just nothing right now,
+    // eg: a gated schedule whose flag is off isn't finished
+    fn is_finished(&self, after: DateTime<Utc>) -> bool {
+        self.next_occurrence(after).is_none()
+    }
+
+    // Fire a fixed duration before each occurrence of this schedule
+    fn lead_by(self, lead: Duration) -> LeadSchedule
+    where
+        Self: Sized + 'static,
+    {
+        LeadSchedule::new(Box::new(self), lead)
+    }
+
+    // Round occurrences down to a multiple of `to` so jobs coalesce into shared batches
+    fn quantize(self, to: Duration) -> Result<QuantizedSchedule, SchedulerError>
+    where
+        Self: Sized + 'static,
+    {
+        QuantizedSchedule::new(Box::new(self), to)
+    }
+
+    // Suppress occurrences while `enabled` returns false, eg: for feature flag rollouts
+    fn gated_by(self, enabled: Box<dyn Fn() -> bool>) -> GatedSchedule
+    where
+        Self: Sized + 'static,
+    {
+        GatedSchedule::new(Box::new(self), enabled)
+    }
+
+    // Count the occurrences this schedule hands to filters wrapped around it and those they drop,
+    // read through InstrumentedSchedule::counter
+    fn instrumented(self) -> InstrumentedSchedule
+    where
+        Self: Sized + 'static,
+    {
+        InstrumentedSchedule::new(Box::new(self))
+    }
+
+    // Adjust each occurrence with `map`, eg: rounding or shifting by business rules
+    fn map_occurrences(self, map: Box<dyn Fn(DateTime<Utc>) -> DateTime<Utc>>) -> MappedSchedule
+    where
+        Self: Sized + 'static,
+    {
+        MappedSchedule::new(Box::new(self), map)
+    }
+
+    // Let an executor hold each fire back by up to `window`, to batch it with other due jobs
+    fn with_slack(self, window: Duration) -> SlackSchedule
+    where
+        Self: Sized + 'static,
+    {
+        SlackSchedule::new(Box::new(self), window)
+    }
+
+    // How long an executor may put off a due fire, see with_slack
+    fn slack(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    // Tell the schedule its occurrence at `time` was handed out, eg: fired by a job, so that
+    // schedules counting their fires, such as RepeatNSchedule, count it. Asking for occurrences,
+    // eg: for a preview, never counts. Wrappers pass it on to the schedule they wrap
+    fn record_fire(&self, _time: DateTime<Utc>) {}
+
+    // How many more occurrences the schedule will hand out, or None if it isn't capped
+    fn remaining_occurrences(&self) -> Option<u32> {
+        None
+    }
+
+    // Report no occurrence more than `horizon` past the first time the schedule is asked
+    fn capped_horizon(self, horizon: Duration) -> CappedHorizonSchedule
+    where
+        Self: Sized + 'static,
+    {
+        CappedHorizonSchedule::new(Box::new(self), horizon)
+    }
+
+    // Move each occurrence into the `earliest` to `latest` time of day on its own day
+    fn clamp_daily(
+        self,
+        earliest: NaiveTime,
+        latest: NaiveTime,
+    ) -> Result<ClampedSchedule, SchedulerError>
+    where
+        Self: Sized + 'static,
+    {
+        ClampedSchedule::new(Box::new(self), earliest, latest)
+    }
+
+    // Standard five-field cron expression, when the schedule can be written as one
+    fn to_cron_expression(&self) -> Option<String> {
+        None
+    }
+
+    // Human readable summary of the schedule
+    fn describe(&self) -> String {
+        String::from("custom schedule")
+    }
+}
+
+// Pass concrete schedules where a boxed one is expected
+macro_rules! impl_into_boxed_schedule {
+    ($($schedule:ty),* $(,)?) => {
+        $(
+            impl From<$schedule> for Box<dyn Schedule> {
+                fn from(schedule: $schedule) -> Self {
+                    Box::new(schedule)
+                }
+            }
+        )*
+    };
+}
+
+impl_into_boxed_schedule!(
+    ApproachingSchedule,
+    BusinessDaySchedule,
+    BusinessHoursIntervalSchedule,
+    CappedHorizonSchedule,
+    ClampedSchedule,
+    CombinedSchedule,
+    CronSchedule,
+    CustomEventSchedule,
+    DailyTimesSchedule,
+    ExceptSchedule,
+    ExplicitSchedule,
+    FilteredIntervalSchedule,
+    FiscalSchedule,
+    GatedSchedule,
+    InstrumentedSchedule,
+    IntervalSchedule,
+    LayeredSchedule,
+    LeadSchedule,
+    MappedSchedule,
+    MonthlySchedule,
+    NthBusinessDaySchedule,
+    NthWeekdaySchedule,
+    OneTimeSchedule,
+    QuantizedSchedule,
+    RandomIntervalSchedule,
+    RandomWindowSchedule,
+    RepeatNSchedule,
+    SlackSchedule,
+    TradingSchedule,
+    WeeklySchedule,
+);
+
+#[cfg(any(test, feature = "test-util"))]
+impl_into_boxed_schedule!(RecordingSchedule);
+
+// Keep already boxed concrete schedules working alongside unboxed ones
+impl<S: Schedule + 'static> From<Box<S>> for Box<dyn Schedule> {
+    fn from(schedule: Box<S>) -> Self {
+        schedule
+    }
+}
+
+impl fmt::Display for dyn Schedule + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe())
+    }
+}
+
+// How a wall-clock schedule handles times a DST change skips or repeats
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DstPolicy {
+    // Skip times a spring-forward gap removes; repeated fall-back times fire on the first pass
+    #[default]
+    Skip,
+    // Fire times in a gap when it ends; repeated times fire on the first pass
+    NextValid,
+    // Fire times in a gap when it ends, and repeated times on both passes
+    RunBoth,
+}
+
+// Clock a schedule's fields or grid are laid out on
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) enum WallClock {
+    #[default]
+    Utc,
+    // The machine's zone, read each time an occurrence is computed
+    Local,
+    #[cfg(feature = "chrono-tz")]
+    Named(chrono_tz::Tz),
+}
+
+impl WallClock {
+    // Wall-clock time of `time` on this clock, written as if it were UTC
+    pub(crate) fn wall_time(self, time: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            WallClock::Utc => time,
+            WallClock::Local => time.with_timezone(&chrono::Local).naive_local().and_utc(),
+            #[cfg(feature = "chrono-tz")]
+            WallClock::Named(tz) => time.with_timezone(&tz).naive_local().and_utc(),
+        }
+    }
+
+    // Next instant after `after` at a wall-clock time picked by `next_wall`, which searches
+    // wall-clock time as if it were UTC
+    pub(crate) fn next_after(
+        self,
+        after: DateTime<Utc>,
+        policy: DstPolicy,
+        next_wall: impl Fn(DateTime<Utc>) -> Option<DateTime<Utc>>,
+    ) -> Option<DateTime<Utc>> {
+        match self {
+            WallClock::Utc => next_wall(after),
+            WallClock::Local => next_on_wall_clock(after, &chrono::Local, policy, next_wall),
+            #[cfg(feature = "chrono-tz")]
+            WallClock::Named(tz) => next_on_wall_clock(after, &tz, policy, next_wall),
+        }
+    }
+
+    // Suffix for schedule descriptions
+    pub(crate) fn describe(self) -> String {
+        match self {
+            WallClock::Utc => String::new(),
+            WallClock::Local => String::from(" local time"),
+            #[cfg(feature = "chrono-tz")]
+            WallClock::Named(tz) => format!(" in {tz}"),
+        }
+    }
+}
+
+// Stored by name: UTC, Local, or an IANA zone such as America/New_York
+#[cfg(feature = "serde")]
+impl serde::Serialize for WallClock {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            WallClock::Utc => serializer.serialize_str("UTC"),
+            WallClock::Local => serializer.serialize_str("Local"),
+            #[cfg(feature = "chrono-tz")]
+            WallClock::Named(tz) => serializer.serialize_str(tz.name()),
+        }
+    }
+}
+
+// Unknown zone names are an error rather than falling back to UTC
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for WallClock {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        match name.as_str() {
+            "UTC" => return Ok(WallClock::Utc),
+            "Local" => return Ok(WallClock::Local),
+            _ => {}
+        }
+        #[cfg(feature = "chrono-tz")]
+        if let Ok(tz) = name.parse() {
+            return Ok(WallClock::Named(tz));
+        }
+        Err(serde::de::Error::custom(SchedulerError::InvalidCronField {
+            field: "timezone",
+            value: name,
+        }))
+    }
+}
+
+pub(crate) fn next_on_wall_clock<Tz: TimeZone>(
+    after: DateTime<Utc>,
+    tz: &Tz,
+    policy: DstPolicy,
+    next_wall: impl Fn(DateTime<Utc>) -> Option<DateTime<Utc>>,
+) -> Option<DateTime<Utc>> {
+    // Back off a second so a repeated time's second pass is still ahead when `after` is the first
+    let mut wall_clock =
+        after.with_timezone(tz).naive_local().and_utc() - chrono::TimeDelta::seconds(1);
+
+    loop {
+        let candidate = next_wall(wall_clock)?;
+        let instants = match tz.from_local_datetime(&candidate.naive_utc()) {
+            chrono::LocalResult::Single(local) => vec![local],
+            chrono::LocalResult::Ambiguous(first, second) if policy == DstPolicy::RunBoth => {
+                vec![first, second]
+            }
+            chrono::LocalResult::Ambiguous(first, _) => vec![first],
+            chrono::LocalResult::None if policy == DstPolicy::Skip => Vec::new(),
+            chrono::LocalResult::None => gap_end(tz, candidate).into_iter().collect(),
+        };
+
+        let next = instants
+            .into_iter()
+            .map(|local| local.with_timezone(&Utc))
+            .find(|&instant| instant > after);
+        if next.is_some() {
+            return next;
+        }
+        wall_clock = candidate;
+    }
+}
+
+// First valid local time after the DST gap containing `wall`
+fn gap_end<Tz: TimeZone>(tz: &Tz, wall: DateTime<Utc>) -> Option<DateTime<Tz>> {
+    let minute = wall.naive_utc().with_second(0)?.with_nanosecond(0)?;
+    (1..=24 * 60).find_map(|minutes| {
+        tz.from_local_datetime(&(minute + chrono::TimeDelta::minutes(minutes)))
+            .earliest()
+    })
+}
+
+// Latest occurrence before `before`, found by widening a look-back window until it holds one
+pub(crate) fn latest_before<S: Schedule + ?Sized>(
+    schedule: &S,
+    before: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    // Anything further back than a full 400 year calendar cycle would have repeated since
+    let mut look_back = chrono::TimeDelta::seconds(1);
+    while look_back <= chrono::TimeDelta::days(146_097) {
+        let mut previous = before.checked_sub_signed(look_back)?;
+        let mut latest = None;
+        while let Some(next) = schedule.next_occurrence(previous) {
+            if next >= before || next <= previous {
+                break;
+            }
+            latest = Some(next);
+            previous = next;
+        }
+        if latest.is_some() {
+            return latest;
+        }
+        look_back = look_back * 2;
+    }
+    None
+}
+
+// Iterator over a schedule's occurrences, ending when the schedule stops moving forward
+pub struct Occurrences<'a> {
+    schedule: &'a dyn Schedule,
+    start: DateTime<Utc>,
+    previous: DateTime<Utc>,
+    remaining: Option<u32>,
+}
+
+impl<'a> Occurrences<'a> {
+    // Also works for boxed schedules, which can't call Schedule::iter_from
+    pub fn new(schedule: &'a dyn Schedule, start: DateTime<Utc>) -> Self {
+        Self {
+            schedule,
+            start,
+            previous: start - chrono::TimeDelta::seconds(1),
+            remaining: schedule.remaining_occurrences(),
+        }
+    }
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = DateTime<Utc>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let next = self
+                .schedule
+                .next_occurrence(self.previous)
+                .filter(|next| *next > self.previous)?;
+            self.previous = next;
+            if !take_remaining(&mut self.remaining) {
+                return None;
+            }
+            if next >= self.start {
+                return Some(next);
+            }
+        }
+    }
+}
+
+// Whether any occurrence falls within `tolerance` either side of `time`
+pub(crate) fn occurs_within<S: Schedule + ?Sized>(
+    schedule: &S,
+    time: DateTime<Utc>,
+    tolerance: Duration,
+) -> bool {
+    let window_start = time - tolerance - chrono::TimeDelta::nanoseconds(1);
+    schedule
+        .next_occurrence(window_start)
+        .is_some_and(|next| next <= time + tolerance)
+}
+
+// Take one occurrence off a remaining count, or false once there are none left
+pub(crate) fn take_remaining(remaining: &mut Option<u32>) -> bool {
+    match remaining {
+        Some(0) => false,
+        Some(remaining) => {
+            *remaining -= 1;
+            true
+        }
+        None => true,
+    }
+}
+
+// Caps a schedule at `limit` fires, counting the occurrences handed out through record_fire.
+// Nothing is worked out again from earlier occurrences, so the cap holds exactly even for
+// schedules drawing their times at random
+pub(crate) struct OccurrenceCap {
+    limit: u32,
+    fired: Cell<u32>,
+    // Latest fire counted, so hearing about it again doesn't count it twice
+    last_fired: Cell<Option<DateTime<Utc>>>,
+}
+
+impl OccurrenceCap {
+    pub(crate) fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            fired: Cell::new(0),
+            last_fired: Cell::new(None),
+        }
+    }
+
+    pub(crate) fn record(&self, time: DateTime<Utc>) {
+        if self.last_fired.get().is_some_and(|last| time <= last) {
+            return;
+        }
+        self.fired.set(self.fired.get() + 1);
+        self.last_fired.set(Some(time));
+    }
+
+    pub(crate) fn remaining(&self) -> u32 {
+        self.limit.saturating_sub(self.fired.get())
+    }
+
+    pub(crate) fn is_exhausted(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    // Next occurrence of the capped schedule, until the cap is used up
+    pub(crate) fn next(
+        &self,
+        after: DateTime<Utc>,
+        next_inner: &dyn Fn(DateTime<Utc>) -> Option<DateTime<Utc>>,
+    ) -> Option<DateTime<Utc>> {
+        if self.is_exhausted() {
+            return None;
+        }
+        next_inner(after)
+    }
+
+    // Latest occurrence of the capped schedule before `before`, none coming after the last
+    // fire once the cap is used up
+    pub(crate) fn previous(
+        &self,
+        before: DateTime<Utc>,
+        previous_inner: &dyn Fn(DateTime<Utc>) -> Option<DateTime<Utc>>,
+    ) -> Option<DateTime<Utc>> {
+        match self.last_fired.get() {
+            Some(last) if self.is_exhausted() => {
+                previous_inner(before.min(last + chrono::TimeDelta::nanoseconds(1)))
+            }
+            _ => previous_inner(before),
+        }
+    }
 }
@@ -0,0 +1,112 @@
+use super::*;
+
+// What a MonthlySchedule does in months too short for its day, eg: the 31st in April
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShortMonthRule {
+    // Fire on the month's last day instead
+    #[default]
+    ClampToLastDay,
+    // Don't fire that month
+    Skip,
+}
+
+// Schedule firing on a day of every `n`th calendar month, counted from an anchor month
+pub struct MonthlySchedule {
+    n: u32,
+    day: u32,
+    time: NaiveTime,
+    anchor: NaiveDate,
+    short_month: ShortMonthRule,
+}
+
+impl MonthlySchedule {
+    // Every `n` months on the 1st at midnight, counting months from January 1970
+    pub fn every(n: u32) -> Result<Self, SchedulerError> {
+        if n == 0 {
+            return Err(SchedulerError::InvalidRepetition);
+        }
+        Ok(Self {
+            n,
+            day: 1,
+            time: NaiveTime::MIN,
+            anchor: NaiveDate::default(),
+            short_month: ShortMonthRule::default(),
+        })
+    }
+
+    pub fn on_day(mut self, day: u32) -> Result<Self, SchedulerError> {
+        if day == 0 || day > 31 {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+        self.day = day;
+        Ok(self)
+    }
+
+    pub fn at(mut self, hour: u32, minute: u32) -> Result<Self, SchedulerError> {
+        self.time =
+            NaiveTime::from_hms_opt(hour, minute, 0).ok_or(SchedulerError::InvalidDateTime)?;
+        Ok(self)
+    }
+
+    // Count months from the month of `date`, never firing before it
+    pub fn starting(mut self, date: NaiveDate) -> Self {
+        self.anchor = date;
+        self
+    }
+
+    pub fn with_short_month_rule(mut self, rule: ShortMonthRule) -> Self {
+        self.short_month = rule;
+        self
+    }
+
+    // Fire date in the month `months` after the anchor month, if that month has one
+    fn date_in(&self, months: u32) -> Option<NaiveDate> {
+        let first = self
+            .anchor
+            .with_day(1)?
+            .checked_add_months(chrono::Months::new(months))?;
+        let length = first
+            .checked_add_months(chrono::Months::new(1))?
+            .pred_opt()?
+            .day();
+
+        match self.short_month {
+            ShortMonthRule::Skip if self.day > length => None,
+            _ => first.with_day(self.day.min(length)),
+        }
+    }
+}
+
+impl Schedule for MonthlySchedule {
+    fn describe(&self) -> String {
+        let mut description = format!(
+            "every {} months on day {} at {}",
+            self.n,
+            self.day,
+            self.time.format("%H:%M")
+        );
+        if self.short_month == ShortMonthRule::Skip {
+            description.push_str(" (skipping short months)");
+        }
+        description
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        // Start from the last scheduled month at or before `after`
+        let months_since = (after.year() - self.anchor.year()) * 12 + after.month() as i32
+            - self.anchor.month() as i32;
+        let mut months = months_since.max(0) as u32 / self.n * self.n;
+
+        // Month lengths repeat within a few years, so a day that never fits is found quickly
+        for _ in 0..100 {
+            if let Some(date) = self.date_in(months) {
+                let candidate = date.and_time(self.time).and_utc();
+                if candidate > after && date >= self.anchor {
+                    return Some(candidate);
+                }
+            }
+            months = months.checked_add(self.n)?;
+        }
+        None
+    }
+}
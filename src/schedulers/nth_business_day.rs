@@ -0,0 +1,55 @@
+use super::*;
+
+// Schedule firing on every Nth weekday-business-day of each calendar year
+pub struct NthBusinessDaySchedule {
+    n: u32,
+    time: NaiveTime,
+}
+
+impl NthBusinessDaySchedule {
+    pub fn new(n: u32, time: NaiveTime) -> Result<Self, SchedulerError> {
+        if n == 0 {
+            return Err(SchedulerError::InvalidRepetition);
+        }
+        Ok(Self { n, time })
+    }
+}
+
+impl Schedule for NthBusinessDaySchedule {
+    fn describe(&self) -> String {
+        format!(
+            "every {} business day of the year at {}",
+            ordinal(self.n),
+            self.time.format("%H:%M")
+        )
+    }
+
+    fn is_satisfiable(&self) -> bool {
+        // Every year has at least 260 weekdays
+        self.n <= 260
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if !self.is_satisfiable() {
+            return None;
+        }
+
+        // The count restarts on 1 January, so walk from the start of the year
+        let mut date = NaiveDate::from_ymd_opt(after.year(), 1, 1)?;
+        let mut count = 0u32;
+
+        loop {
+            if date.ordinal() == 1 {
+                count = 0;
+            }
+            if !is_weekend(date) {
+                count += 1;
+                let candidate = date.and_time(self.time).and_utc();
+                if count.is_multiple_of(self.n) && candidate > after {
+                    return Some(candidate);
+                }
+            }
+            date = date.succ_opt()?;
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use super::*;
+
+// Schedule firing on the nth given weekday of every month, counting back from the end when
+// negative, eg: the third Saturday, or -1 for the last Friday
+pub struct NthWeekdaySchedule {
+    weekday: Weekday,
+    n: i32,
+    time: NaiveTime,
+}
+
+impl NthWeekdaySchedule {
+    pub fn new(weekday: Weekday, n: i32) -> Result<Self, SchedulerError> {
+        // No month has more than five of any weekday
+        if n == 0 || n.abs() > 5 {
+            return Err(SchedulerError::InvalidRepetition);
+        }
+        Ok(Self {
+            weekday,
+            n,
+            time: NaiveTime::MIN,
+        })
+    }
+
+    // Time of day to fire at, midnight unless set
+    pub fn with_time(mut self, time: NaiveTime) -> Self {
+        self.time = time;
+        self
+    }
+
+    // The matching date in the given month, if the month has enough of the weekday
+    fn date_in(&self, year: i32, month: u32) -> Option<NaiveDate> {
+        let matching: Vec<NaiveDate> = (1..=5)
+            .filter_map(|nth| NaiveDate::from_weekday_of_month_opt(year, month, self.weekday, nth))
+            .collect();
+        let index = match self.n {
+            n if n > 0 => n as usize - 1,
+            n => matching.len().checked_sub(n.unsigned_abs() as usize)?,
+        };
+        matching.get(index).copied()
+    }
+}
+
+impl Schedule for NthWeekdaySchedule {
+    fn describe(&self) -> String {
+        let position = match self.n {
+            -1 => String::from("last"),
+            n if n < 0 => format!("{} from last", ordinal(n.unsigned_abs())),
+            n => ordinal(n as u32),
+        };
+        format!(
+            "{} {} of every month at {}",
+            position,
+            self.weekday,
+            self.time.format("%H:%M")
+        )
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let (mut year, mut month) = (after.year(), after.month());
+
+        // Every weekday comes around five times in some month of any year
+        for _ in 0..=24 {
+            if let Some(date) = self.date_in(year, month) {
+                let candidate = date.and_time(self.time).and_utc();
+                if candidate > after {
+                    return Some(candidate);
+                }
+            }
+            (year, month) = if month == 12 {
+                (year + 1, 1)
+            } else {
+                (year, month + 1)
+            };
+        }
+        None
+    }
+}
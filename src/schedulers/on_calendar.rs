@@ -0,0 +1,187 @@
+use super::*;
+
+impl CronSchedule {
+    // Build a schedule from systemd's OnCalendar= syntax, eg: "Mon..Fri *-*-* 10:00:00"
+    pub fn from_on_calendar(expression: &str) -> Result<Self, SchedulerError> {
+        let expanded = match expression.trim().to_ascii_lowercase().as_str() {
+            "minutely" => "*-*-* *:*:00",
+            "hourly" => "*-*-* *:00:00",
+            "daily" => "*-*-* 00:00:00",
+            "weekly" => "Mon *-*-* 00:00:00",
+            "monthly" => "*-*-01 00:00:00",
+            "yearly" | "annually" => "*-01-01 00:00:00",
+            "quarterly" => "*-01,04,07,10-01 00:00:00",
+            "semiannually" => "*-01,07-01 00:00:00",
+            _ => expression,
+        };
+
+        let mut tokens: Vec<&str> = expanded.split_whitespace().collect();
+        // Occurrences are already computed in UTC, so that is the only zone accepted
+        if tokens
+            .last()
+            .is_some_and(|zone| zone.eq_ignore_ascii_case("UTC"))
+        {
+            tokens.pop();
+        }
+
+        let mut schedule = Self::new();
+        let mut tokens = tokens.into_iter().peekable();
+        if let Some(weekdays) = tokens.next_if(|token| token.starts_with(char::is_alphabetic)) {
+            schedule = schedule
+                .weekdays(parse_weekdays(weekdays)?)
+                .map_err(|_| invalid("weekday", weekdays))?;
+        }
+        if let Some(date) = tokens.next_if(|token| token.contains('-')) {
+            schedule = apply_date(schedule, date)?;
+        }
+        // With no time given, systemd fires at midnight
+        let time = tokens.next().unwrap_or("00:00:00");
+        schedule = apply_time(schedule, time)?;
+
+        match tokens.next() {
+            Some(_) => Err(invalid("expression", expression)),
+            None => Ok(schedule),
+        }
+    }
+}
+
+fn invalid(field: &'static str, value: &str) -> SchedulerError {
+    SchedulerError::InvalidCronField {
+        field,
+        value: value.to_string(),
+    }
+}
+
+// Comma separated weekday names or `..` ranges of them, eg: "Mon..Fri,Sun"
+fn parse_weekdays(token: &str) -> Result<Vec<u32>, SchedulerError> {
+    const NAMES: [&str; 7] = [
+        "monday",
+        "tuesday",
+        "wednesday",
+        "thursday",
+        "friday",
+        "saturday",
+        "sunday",
+    ];
+    // Full names or their first three letters
+    let weekday = |name: &str| {
+        let name = name.to_ascii_lowercase();
+        NAMES
+            .iter()
+            .position(|full| name == *full || name == full[..3])
+            .map(|index| index as u32)
+            .ok_or_else(|| invalid("weekday", token))
+    };
+
+    let mut weekdays = Vec::new();
+    for part in token.split(',') {
+        match part.split_once("..") {
+            Some((start, end)) => {
+                let (start, end) = (weekday(start)?, weekday(end)?);
+                if start > end {
+                    return Err(invalid("weekday", token));
+                }
+                weekdays.extend(start..=end);
+            }
+            None => weekdays.push(weekday(part)?),
+        }
+    }
+    Ok(weekdays)
+}
+
+// `*` for any value, otherwise a comma separated list of numbers, `a..b` ranges, and
+// `a/step` repetitions, all within `allowed`
+fn parse_component(
+    field: &'static str,
+    token: &str,
+    allowed: RangeInclusive<u32>,
+) -> Result<Option<Vec<u32>>, SchedulerError> {
+    if token == "*" {
+        return Ok(None);
+    }
+    let value = |text: &str| {
+        text.parse()
+            .ok()
+            .filter(|value| allowed.contains(value))
+            .ok_or_else(|| invalid(field, token))
+    };
+
+    let mut values = Vec::new();
+    for part in token.split(',') {
+        if let Some((start, step)) = part.split_once('/') {
+            let start = if start == "*" {
+                *allowed.start()
+            } else {
+                value(start)?
+            };
+            let step: u32 = step.parse().map_err(|_| invalid(field, token))?;
+            if step == 0 {
+                return Err(invalid(field, token));
+            }
+            values.extend((start..=*allowed.end()).step_by(step as usize));
+        } else if let Some((start, end)) = part.split_once("..") {
+            let (start, end) = (value(start)?, value(end)?);
+            if start > end {
+                return Err(invalid(field, token));
+            }
+            values.extend(start..=end);
+        } else {
+            values.push(value(part)?);
+        }
+    }
+    Ok(Some(values))
+}
+
+// Date as year-month-day or month-day, eg: "*-*-01" or "2024-01,07-*"
+fn apply_date(schedule: CronSchedule, date: &str) -> Result<CronSchedule, SchedulerError> {
+    let parts: Vec<&str> = date.split('-').collect();
+    let (year, month, day) = match parts[..] {
+        [year, month, day] => (Some(year), month, day),
+        [month, day] => (None, month, day),
+        _ => return Err(invalid("date", date)),
+    };
+
+    let mut schedule = schedule;
+    if let Some(year) = year {
+        if let Some(years) = parse_component("year", year, 1970..=2099)? {
+            schedule = schedule
+                .years(years.into_iter().map(|year| year as i32))
+                .map_err(|_| invalid("year", year))?;
+        }
+    }
+    if let Some(months) = parse_component("month", month, 1..=12)? {
+        schedule = schedule
+            .months(months)
+            .map_err(|_| invalid("month", month))?;
+    }
+    if let Some(days) = parse_component("day", day, 1..=31)? {
+        schedule = schedule.days(days).map_err(|_| invalid("day", day))?;
+    }
+    Ok(schedule)
+}
+
+// Time as hour:minute or hour:minute:second, eg: "10:00" or "*:0/15:00"
+fn apply_time(schedule: CronSchedule, time: &str) -> Result<CronSchedule, SchedulerError> {
+    let parts: Vec<&str> = time.split(':').collect();
+    let (hour, minute, second) = match parts[..] {
+        [hour, minute] => (hour, minute, "00"),
+        [hour, minute, second] => (hour, minute, second),
+        _ => return Err(invalid("time", time)),
+    };
+
+    let mut schedule = schedule;
+    if let Some(hours) = parse_component("hour", hour, 0..=23)? {
+        schedule = schedule.hours(hours).map_err(|_| invalid("hour", hour))?;
+    }
+    if let Some(minutes) = parse_component("minute", minute, 0..=59)? {
+        schedule = schedule
+            .minutes(minutes)
+            .map_err(|_| invalid("minute", minute))?;
+    }
+    // Second zero is already the default, so only other seconds need the seconds field
+    match parse_component("second", second, 0..=59)? {
+        Some(seconds) if seconds != [0] => schedule.seconds(seconds),
+        Some(_) => Ok(schedule),
+        None => schedule.seconds(0..60),
+    }
+}
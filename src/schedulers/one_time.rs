@@ -2,7 +2,10 @@ use super::*;
 
 // Specific date/time schedule
 pub struct OneTimeSchedule {
-    time: DateTime<Utc>,
+    // None for an immediate schedule, which has no time of its own
+    time: Option<DateTime<Utc>>,
+    // Set once the fire is handed out through record_fire, eg: by a job
+    fired: Cell<bool>,
 }
 
 impl OneTimeSchedule {
@@ -10,16 +13,69 @@ impl OneTimeSchedule {
         if time <= Utc::now() {
             return Err(SchedulerError::TimeInPast);
         }
-        Ok(Self { time })
+        Ok(Self {
+            time: Some(time),
+            fired: Cell::new(false),
+        })
+    }
+
+    // Due as soon as it is asked about, however long after creation, until it has fired once.
+    // A job fires it on its first poll, and the job's last fire keeps when that was
+    pub fn immediate() -> Self {
+        Self {
+            time: None,
+            fired: Cell::new(false),
+        }
+    }
+
+    // Fire at the one-time instant, then every `interval` anchored to it
+    pub fn then_every(self, interval: Duration) -> Result<IntervalSchedule, SchedulerError> {
+        // An immediate schedule has no fixed instant to anchor the interval to
+        let time = self.time.ok_or(SchedulerError::InvalidConfiguration)?;
+        IntervalSchedule::new(interval, time)
     }
 }
 
 impl Schedule for OneTimeSchedule {
+    fn span(&self) -> Option<Duration> {
+        Some(Duration::ZERO)
+    }
+
+    fn describe(&self) -> String {
+        match self.time {
+            Some(time) => format!("once at {time}"),
+            None => String::from("once, immediately"),
+        }
+    }
+
+    fn is_finished(&self, after: DateTime<Utc>) -> bool {
+        match self.time {
+            Some(time) => after >= time,
+            None => self.fired.get(),
+        }
+    }
+
+    fn previous_occurrence(&self, before: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.time.filter(|time| *time < before)
+    }
+
     fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
-        if after < self.time {
-            Some(self.time)
-        } else {
-            None
+        match self.time {
+            Some(time) => (after < time).then_some(time),
+            // Due a second on, where a poll backing off a second asks from
+            None => (!self.fired.get()).then(|| after + chrono::TimeDelta::seconds(1)),
+        }
+    }
+
+    fn record_fire(&self, _time: DateTime<Utc>) {
+        self.fired.set(true);
+    }
+
+    // Keeps an immediate schedule, due whenever it is asked about, to the one fire
+    fn remaining_occurrences(&self) -> Option<u32> {
+        match self.time {
+            Some(_) => None,
+            None => Some(u32::from(!self.fired.get())),
         }
     }
 }
@@ -0,0 +1,69 @@
+use super::*;
+
+// Schedule rounding occurrences down to a coarser time grid
+pub struct QuantizedSchedule {
+    inner: Box<dyn Schedule>,
+    step: Duration,
+}
+
+impl QuantizedSchedule {
+    pub fn new(inner: Box<dyn Schedule>, step: Duration) -> Result<Self, SchedulerError> {
+        if step.as_secs() == 0 {
+            return Err(SchedulerError::InvalidDuration);
+        }
+        Ok(Self { inner, step })
+    }
+
+    fn round_down(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let step = self.step.as_secs() as i64;
+        DateTime::from_timestamp(time.timestamp().div_euclid(step) * step, 0)
+    }
+}
+
+impl Schedule for QuantizedSchedule {
+    fn is_satisfiable(&self) -> bool {
+        self.inner.is_satisfiable()
+    }
+
+    fn validate(&self) -> Result<(), SchedulerError> {
+        self.inner.validate()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} rounded down to {}",
+            self.inner.describe(),
+            format_duration(self.step)
+        )
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let rounded = self.round_down(self.inner.next_occurrence(after)?)?;
+        if rounded > after {
+            return Some(rounded);
+        }
+
+        // Rounding fell back to or before `after`, so only an occurrence in a later slot can
+        // fire after it. Ask again from where the slot holding `after` ends, which also gets a
+        // fresh draw from schedules answering from a fixed start, whatever they are asked
+        let step = chrono::TimeDelta::from_std(self.step).ok()?;
+        let slot_end = self.round_down(after)?.checked_add_signed(step)?;
+        let next = self
+            .inner
+            .next_occurrence(slot_end - chrono::TimeDelta::nanoseconds(1))?;
+        self.round_down(next).filter(|rounded| *rounded > after)
+    }
+
+    // The fire stands in for the inner occurrence rounded down to it, the first in its slot
+    fn record_fire(&self, time: DateTime<Utc>) {
+        let occurrence = self
+            .inner
+            .next_occurrence(time - chrono::TimeDelta::nanoseconds(1))
+            .unwrap_or(time);
+        self.inner.record_fire(occurrence);
+    }
+
+    fn remaining_occurrences(&self) -> Option<u32> {
+        self.inner.remaining_occurrences()
+    }
+}
@@ -1,11 +1,18 @@
 use super::*;
 
+// Snapshot of a seeded schedule's random sequence, for resuming after a restart
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RngState(ChaCha12Rng);
+
 // Random interval schedule
 pub struct RandomIntervalSchedule {
     min_interval: Duration,
     max_interval: Duration,
     last_time: Option<DateTime<Utc>>,
     end_time: Option<DateTime<Utc>>,
+    seed: Option<u64>,
+    rng: Option<RefCell<ChaCha12Rng>>,
 }
 
 impl RandomIntervalSchedule {
@@ -21,6 +28,8 @@ impl RandomIntervalSchedule {
             max_interval,
             last_time: None,
             end_time: None,
+            seed: None,
+            rng: None,
         })
     }
 
@@ -34,17 +43,43 @@ impl RandomIntervalSchedule {
         self
     }
 
+    // Draw intervals from a seeded generator so runs are reproducible
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self.rng = Some(RefCell::new(ChaCha12Rng::seed_from_u64(seed)));
+        self
+    }
+
+    // Deterministic stand-in replaying fire times observed from a past run
+    pub fn replay(times: Vec<DateTime<Utc>>) -> Result<ExplicitSchedule, SchedulerError> {
+        ExplicitSchedule::new(times)
+    }
+
+    // Current position in the seeded random sequence, if the schedule is seeded
+    pub fn rng_state(&self) -> Option<RngState> {
+        self.rng.as_ref().map(|rng| RngState(rng.borrow().clone()))
+    }
+
+    // Continue the random sequence from a previously taken snapshot
+    pub fn restore_rng_state(&mut self, state: RngState) {
+        self.rng = Some(RefCell::new(state.0));
+    }
+
     fn generate_random_interval(&self) -> Duration {
-        let mut rng = rand::rng();
+        match &self.rng {
+            Some(rng) => self.random_interval_from(&mut *rng.borrow_mut()),
+            None => self.random_interval_from(&mut rand::rng()),
+        }
+    }
+
+    fn random_interval_from<R: Rng>(&self, rng: &mut R) -> Duration {
         let secs = rng.random_range(self.min_interval.as_secs()..=self.max_interval.as_secs());
         Duration::from_secs(secs)
     }
-}
 
-impl Schedule for RandomIntervalSchedule {
-    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    fn occurrence_after(&self, after: DateTime<Utc>, interval: Duration) -> Option<DateTime<Utc>> {
         let last_time = self.last_time.unwrap_or(after);
-        let next_time = last_time + self.generate_random_interval();
+        let next_time = last_time + interval;
 
         match self.end_time {
             Some(end) if next_time > end => None,
@@ -52,3 +87,51 @@ impl Schedule for RandomIntervalSchedule {
         }
     }
 }
+
+impl Schedule for RandomIntervalSchedule {
+    fn describe(&self) -> String {
+        let mut description = format!(
+            "randomly every {} to {}",
+            format_duration(self.min_interval),
+            format_duration(self.max_interval)
+        );
+        if let Some(end) = self.end_time {
+            description.push_str(&format!(" until {end}"));
+        }
+        description
+    }
+
+    fn first_occurrence(&self, from: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self.seed {
+            // Replay the first draw of the seeded sequence without consuming it
+            Some(seed) => {
+                let interval = self.random_interval_from(&mut ChaCha12Rng::seed_from_u64(seed));
+                self.occurrence_after(from, interval)
+            }
+            None => self.next_occurrence(from),
+        }
+    }
+
+    fn is_satisfiable(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    // An end time too close to the start time for even the shortest interval
+    fn validate(&self) -> Result<(), SchedulerError> {
+        match (self.last_time, self.end_time) {
+            (Some(start), Some(end)) if start + self.min_interval > end => {
+                Err(SchedulerError::InvalidConfiguration)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Past draws aren't kept, so there is nothing to look back on
+    fn previous_occurrence(&self, _before: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.occurrence_after(after, self.generate_random_interval())
+    }
+}
@@ -0,0 +1,57 @@
+use super::*;
+
+// Schedule firing once a day at a uniformly random instant inside a time-of-day window
+pub struct RandomWindowSchedule {
+    start: NaiveTime,
+    window: Duration,
+    seed: u64,
+}
+
+impl RandomWindowSchedule {
+    // Random instant between the start of the first hour and the start of the last, eg: 9..10
+    pub fn daily(hours: Range<u32>) -> Result<Self, SchedulerError> {
+        if hours.start >= hours.end || hours.end > 24 {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+        Ok(Self {
+            start: NaiveTime::from_hms_opt(hours.start, 0, 0)
+                .ok_or(SchedulerError::InvalidDateTime)?,
+            window: Duration::from_secs(3600 * hours.len() as u64),
+            seed: rand::rng().random(),
+        })
+    }
+
+    // Seed the daily picks so they are reproducible across runs
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    // The day's pick, fixed per date so repeated polls agree
+    fn instant_on(&self, date: NaiveDate) -> DateTime<Utc> {
+        let seed = self.seed ^ date.num_days_from_ce() as u64;
+        let secs = ChaCha12Rng::seed_from_u64(seed).random_range(0..self.window.as_secs());
+        date.and_time(self.start).and_utc() + Duration::from_secs(secs)
+    }
+}
+
+impl Schedule for RandomWindowSchedule {
+    fn describe(&self) -> String {
+        let end = self.start + self.window;
+        format!(
+            "daily at a random time between {} and {}",
+            self.start.format("%H:%M"),
+            end.format("%H:%M")
+        )
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let today = after.date_naive();
+        let tomorrow = today.succ_opt()?;
+
+        [today, tomorrow]
+            .into_iter()
+            .map(|date| self.instant_on(date))
+            .find(|next| *next > after)
+    }
+}
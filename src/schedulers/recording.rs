@@ -0,0 +1,60 @@
+use super::*;
+use std::rc::Rc;
+
+// Schedule recording every distinct occurrence it emits, for asserting on compositions in tests
+pub struct RecordingSchedule {
+    inner: Box<dyn Schedule>,
+    recorded: Rc<RefCell<Vec<DateTime<Utc>>>>,
+}
+
+impl RecordingSchedule {
+    pub fn new(inner: Box<dyn Schedule>) -> Self {
+        Self {
+            inner,
+            recorded: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    // Shared handle to the recorded occurrences, still readable once the schedule is moved into a job
+    pub fn recording(&self) -> Rc<RefCell<Vec<DateTime<Utc>>>> {
+        Rc::clone(&self.recorded)
+    }
+}
+
+impl Schedule for RecordingSchedule {
+    fn is_satisfiable(&self) -> bool {
+        self.inner.is_satisfiable()
+    }
+
+    fn validate(&self) -> Result<(), SchedulerError> {
+        self.inner.validate()
+    }
+
+    fn describe(&self) -> String {
+        self.inner.describe()
+    }
+
+    // Looking back isn't an emitted occurrence, so nothing is recorded
+    fn previous_occurrence(&self, before: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.inner.previous_occurrence(before)
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let next = self.inner.next_occurrence(after)?;
+
+        // Repeated polls for the same occurrence are only recorded once
+        let mut recorded = self.recorded.borrow_mut();
+        if recorded.last() != Some(&next) {
+            recorded.push(next);
+        }
+        Some(next)
+    }
+
+    fn record_fire(&self, time: DateTime<Utc>) {
+        self.inner.record_fire(time);
+    }
+
+    fn remaining_occurrences(&self) -> Option<u32> {
+        self.inner.remaining_occurrences()
+    }
+}
@@ -0,0 +1,64 @@
+use super::*;
+
+// Schedule forwarding to another schedule for a fixed number of occurrences, then stopping.
+// Each fire handed out through record_fire counts, eg: by a job, while asking for occurrences,
+// eg: for a preview, doesn't
+pub struct RepeatNSchedule {
+    inner: Box<dyn Schedule>,
+    n: u32,
+    cap: OccurrenceCap,
+}
+
+impl RepeatNSchedule {
+    pub fn new(inner: Box<dyn Schedule>, n: u32) -> Self {
+        Self {
+            inner,
+            n,
+            cap: OccurrenceCap::new(n),
+        }
+    }
+}
+
+impl Schedule for RepeatNSchedule {
+    fn is_satisfiable(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    fn validate(&self) -> Result<(), SchedulerError> {
+        if self.n == 0 {
+            return Err(SchedulerError::InvalidRepetition);
+        }
+        self.inner.validate()
+    }
+
+    fn describe(&self) -> String {
+        format!("{} for {} times", self.inner.describe(), self.n)
+    }
+
+    fn is_finished(&self, after: DateTime<Utc>) -> bool {
+        self.cap.is_exhausted() || self.inner.is_finished(after)
+    }
+
+    fn previous_occurrence(&self, before: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.cap
+            .previous(before, &|t| self.inner.previous_occurrence(t))
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.cap.next(after, &|t| self.inner.next_occurrence(t))
+    }
+
+    fn record_fire(&self, time: DateTime<Utc>) {
+        self.cap.record(time);
+        self.inner.record_fire(time);
+    }
+
+    fn remaining_occurrences(&self) -> Option<u32> {
+        let remaining = self.cap.remaining();
+        Some(
+            self.inner
+                .remaining_occurrences()
+                .map_or(remaining, |inner| inner.min(remaining)),
+        )
+    }
+}
@@ -0,0 +1,55 @@
+use super::*;
+
+// Schedule an executor may fire a little late, so it can go out together with other due jobs
+pub struct SlackSchedule {
+    inner: Box<dyn Schedule>,
+    slack: Duration,
+}
+
+impl SlackSchedule {
+    pub fn new(inner: Box<dyn Schedule>, slack: Duration) -> Self {
+        Self { inner, slack }
+    }
+}
+
+impl Schedule for SlackSchedule {
+    fn is_satisfiable(&self) -> bool {
+        self.inner.is_satisfiable()
+    }
+
+    fn validate(&self) -> Result<(), SchedulerError> {
+        self.inner.validate()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} give or take {}",
+            self.inner.describe(),
+            format_duration(self.slack)
+        )
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.inner.next_occurrence(after)
+    }
+
+    fn previous_occurrence(&self, before: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.inner.previous_occurrence(before)
+    }
+
+    fn is_finished(&self, after: DateTime<Utc>) -> bool {
+        self.inner.is_finished(after)
+    }
+
+    fn slack(&self) -> Duration {
+        self.slack
+    }
+
+    fn record_fire(&self, time: DateTime<Utc>) {
+        self.inner.record_fire(time);
+    }
+
+    fn remaining_occurrences(&self) -> Option<u32> {
+        self.inner.remaining_occurrences()
+    }
+}
@@ -0,0 +1,103 @@
+use super::*;
+
+// End of the trading session a TradingSchedule's offset counts from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionEdge {
+    Open,
+    Close,
+}
+
+// Schedule firing at a fixed offset from the open or close of each trading session, eg: five
+// minutes after the open on every day the exchange trades
+pub struct TradingSchedule {
+    calendar: Arc<Calendar>,
+    open: NaiveTime,
+    close: NaiveTime,
+    edge: SessionEdge,
+    offset: Duration,
+}
+
+impl TradingSchedule {
+    // Sessions from `open` to `close` on the calendar's business days, firing at the open
+    pub fn new(
+        calendar: Arc<Calendar>,
+        open: NaiveTime,
+        close: NaiveTime,
+    ) -> Result<Self, SchedulerError> {
+        if open >= close {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+        Ok(Self {
+            calendar,
+            open,
+            close,
+            edge: SessionEdge::Open,
+            offset: Duration::ZERO,
+        })
+    }
+
+    pub fn after_open(mut self, offset: Duration) -> Self {
+        self.edge = SessionEdge::Open;
+        self.offset = offset;
+        self
+    }
+
+    pub fn before_close(mut self, offset: Duration) -> Self {
+        self.edge = SessionEdge::Close;
+        self.offset = offset;
+        self
+    }
+
+    // Open and close of the session on `date`, closing early where the calendar says so
+    fn session(&self, date: NaiveDate) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        if !self.calendar.is_business_day(date) {
+            return None;
+        }
+        let close = self
+            .calendar
+            .early_close(date)
+            .map_or(self.close, |early| early.min(self.close));
+        Some((
+            date.and_time(self.open).and_utc(),
+            date.and_time(close).and_utc(),
+        ))
+    }
+
+    // Fire time on `date`, if it trades and the offset lands within that day's session
+    fn fire_on(&self, date: NaiveDate) -> Option<DateTime<Utc>> {
+        let (open, close) = self.session(date)?;
+        let offset = chrono::TimeDelta::from_std(self.offset).ok()?;
+        let fire = match self.edge {
+            SessionEdge::Open => open + offset,
+            SessionEdge::Close => close - offset,
+        };
+        (open <= fire && fire <= close).then_some(fire)
+    }
+}
+
+impl Schedule for TradingSchedule {
+    fn describe(&self) -> String {
+        let edge = match self.edge {
+            SessionEdge::Open => "after the open",
+            SessionEdge::Close => "before the close",
+        };
+        format!(
+            "{} {edge} on the {} calendar",
+            format_duration(self.offset),
+            self.calendar.name()
+        )
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut date = after.date_naive();
+
+        // A calendar without a trading day in a decade has nothing left to fire on
+        for _ in 0..3660 {
+            if let Some(fire) = self.fire_on(date).filter(|fire| *fire > after) {
+                return Some(fire);
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
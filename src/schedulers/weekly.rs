@@ -0,0 +1,88 @@
+use super::*;
+
+// Schedule firing on given weekdays of every `n`th week, counted from an anchor week, eg:
+// every other Tuesday at 10:00
+pub struct WeeklySchedule {
+    n: u32,
+    weekdays: Vec<Weekday>,
+    time: NaiveTime,
+    anchor: NaiveDate,
+}
+
+impl WeeklySchedule {
+    // Every `n` weeks at midnight, counting Monday-to-Sunday weeks from January 1970
+    pub fn every(n: u32) -> Result<Self, SchedulerError> {
+        if n == 0 {
+            return Err(SchedulerError::InvalidRepetition);
+        }
+        Ok(Self {
+            n,
+            weekdays: Vec::new(),
+            time: NaiveTime::MIN,
+            anchor: NaiveDate::default(),
+        })
+    }
+
+    // Add a weekday to fire on; with none, the anchor date's weekday is used
+    pub fn on(mut self, weekday: Weekday) -> Self {
+        if !self.weekdays.contains(&weekday) {
+            self.weekdays.push(weekday);
+            self.weekdays
+                .sort_by_key(|weekday| weekday.num_days_from_monday());
+        }
+        self
+    }
+
+    pub fn at(mut self, hour: u32, minute: u32) -> Result<Self, SchedulerError> {
+        self.time =
+            NaiveTime::from_hms_opt(hour, minute, 0).ok_or(SchedulerError::InvalidDateTime)?;
+        Ok(self)
+    }
+
+    // Count weeks from the week containing `date`, never firing before it
+    pub fn starting(mut self, date: NaiveDate) -> Self {
+        self.anchor = date;
+        self
+    }
+
+    fn weekdays(&self) -> Vec<Weekday> {
+        if self.weekdays.is_empty() {
+            vec![self.anchor.weekday()]
+        } else {
+            self.weekdays.clone()
+        }
+    }
+}
+
+impl Schedule for WeeklySchedule {
+    fn describe(&self) -> String {
+        let weekdays: Vec<String> = self.weekdays().iter().map(Weekday::to_string).collect();
+        format!(
+            "every {} weeks on {} at {}",
+            self.n,
+            weekdays.join(", "),
+            self.time.format("%H:%M")
+        )
+    }
+
+    fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let anchor_week = self.anchor.week(Weekday::Mon).first_day();
+        let weekdays = self.weekdays();
+
+        // Start from the last scheduled week at or before `after`
+        let weeks_since = (after.date_naive() - anchor_week).num_days().div_euclid(7);
+        let mut weeks = weeks_since.max(0) as u64 / self.n as u64 * self.n as u64;
+
+        loop {
+            let week = anchor_week.checked_add_days(chrono::Days::new(weeks * 7))?;
+            for weekday in &weekdays {
+                let date = week + chrono::Days::new(weekday.num_days_from_monday() as u64);
+                let candidate = date.and_time(self.time).and_utc();
+                if candidate > after && date >= self.anchor {
+                    return Some(candidate);
+                }
+            }
+            weeks += self.n as u64;
+        }
+    }
+}
@@ -74,7 +74,7 @@ fn test_repetition_scenario() {
 
     // Run through all 10 executions
     for i in 1..10 {
-        let next_time = start_time + interval * i as u32;
+        let next_time = start_time + interval * i;
         assert!(job.should_execute(next_time).is_some());
         assert_eq!(job.repeats, i + 1);
     }
@@ -552,3 +552,4405 @@ fn test_mixture_scenario() {
     let after_all_schedules = expected_11pm + Duration::from_secs(60);
     assert!(job.should_execute(after_all_schedules).is_none());
 }
+
+#[test]
+fn test_business_hours_interval_over_weekend() {
+    // Every 4 business hours, 9am-5pm, started on Friday afternoon
+    let friday_3pm = Utc.with_ymd_and_hms(2023, 3, 3, 15, 0, 0).unwrap();
+    let nine_am = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    let five_pm = NaiveTime::from_hms_opt(17, 0, 0).unwrap();
+    let schedule = BusinessHoursIntervalSchedule::new(
+        Duration::from_secs(4 * 3600),
+        friday_3pm,
+        nine_am,
+        five_pm,
+    )
+    .unwrap();
+
+    // 2 business hours on Friday, the remaining 2 on Monday morning
+    let monday_11am = Utc.with_ymd_and_hms(2023, 3, 6, 11, 0, 0).unwrap();
+    let monday_3pm = Utc.with_ymd_and_hms(2023, 3, 6, 15, 0, 0).unwrap();
+
+    assert_eq!(
+        schedule.next_occurrence(friday_3pm - Duration::from_secs(1)),
+        Some(friday_3pm)
+    );
+    assert_eq!(schedule.next_occurrence(friday_3pm), Some(monday_11am));
+
+    // Polling during the weekend still resumes on Monday
+    let saturday_noon = Utc.with_ymd_and_hms(2023, 3, 4, 12, 0, 0).unwrap();
+    assert_eq!(schedule.next_occurrence(saturday_noon), Some(monday_11am));
+    assert_eq!(schedule.next_occurrence(monday_11am), Some(monday_3pm));
+
+    // Without weekend skipping the interval resumes on Saturday morning
+    let schedule = BusinessHoursIntervalSchedule::new(
+        Duration::from_secs(4 * 3600),
+        friday_3pm,
+        nine_am,
+        five_pm,
+    )
+    .unwrap()
+    .skip_weekends(false);
+    let saturday_11am = Utc.with_ymd_and_hms(2023, 3, 4, 11, 0, 0).unwrap();
+    assert_eq!(schedule.next_occurrence(friday_3pm), Some(saturday_11am));
+
+    // Fractional intervals keep their milliseconds, across closing time too
+    let just_before_close = Utc.with_ymd_and_hms(2023, 3, 3, 16, 59, 58).unwrap();
+    let schedule = BusinessHoursIntervalSchedule::new(
+        Duration::from_millis(1500),
+        just_before_close,
+        nine_am,
+        five_pm,
+    )
+    .unwrap();
+    let fires = schedule.occurrences_limited(just_before_close, 2, monday_3pm);
+    assert_eq!(
+        fires,
+        vec![
+            just_before_close + chrono::TimeDelta::milliseconds(1500),
+            Utc.with_ymd_and_hms(2023, 3, 6, 9, 0, 1).unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn test_business_hours_interval_start_outside_hours() {
+    // Hourly, 9am-5pm, started on Saturday evening
+    let saturday_8pm = Utc.with_ymd_and_hms(2023, 3, 4, 20, 0, 0).unwrap();
+    let nine_am = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    let five_pm = NaiveTime::from_hms_opt(17, 0, 0).unwrap();
+    let schedule = BusinessHoursIntervalSchedule::new(
+        Duration::from_secs(3600),
+        saturday_8pm,
+        nine_am,
+        five_pm,
+    )
+    .unwrap();
+
+    // The first fire waits for Monday's business hours, and the interval counts from there
+    let monday_9am = Utc.with_ymd_and_hms(2023, 3, 6, 9, 0, 0).unwrap();
+    let monday_10am = Utc.with_ymd_and_hms(2023, 3, 6, 10, 0, 0).unwrap();
+    assert_eq!(
+        schedule.next_occurrence(saturday_8pm - Duration::from_secs(1)),
+        Some(monday_9am)
+    );
+    assert_eq!(schedule.next_occurrence(saturday_8pm), Some(monday_9am));
+    assert_eq!(schedule.next_occurrence(monday_9am), Some(monday_10am));
+
+    // Asking decades ahead jumps straight there, landing within business hours
+    let later = Utc.with_ymd_and_hms(2063, 3, 7, 16, 30, 0).unwrap();
+    assert_eq!(
+        schedule.next_occurrence(later),
+        Utc.with_ymd_and_hms(2063, 3, 7, 17, 0, 0).single()
+    );
+}
+
+#[test]
+fn test_business_hours_interval_invalid_window() {
+    let start = Utc.with_ymd_and_hms(2023, 3, 3, 9, 0, 0).unwrap();
+    let nine_am = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    let five_pm = NaiveTime::from_hms_opt(17, 0, 0).unwrap();
+
+    let result =
+        BusinessHoursIntervalSchedule::new(Duration::from_secs(3600), start, five_pm, nine_am);
+    assert!(matches!(result, Err(SchedulerError::InvalidConfiguration)));
+
+    let result =
+        BusinessHoursIntervalSchedule::new(Duration::from_secs(0), start, nine_am, five_pm);
+    assert!(matches!(result, Err(SchedulerError::InvalidDuration)));
+}
+
+#[test]
+fn test_validate_all_reports_failing_indices() {
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let interval = Duration::from_secs(3600);
+
+    let schedules: Vec<Box<dyn Schedule>> = vec![
+        // Valid hourly schedule
+        Box::new(IntervalSchedule::new(interval, start_time).unwrap()),
+        // February 30th never exists
+        Box::new(CronSchedule::new().month(2).unwrap().day(30).unwrap()),
+        // End time before the start time
+        Box::new(
+            IntervalSchedule::new(interval, start_time)
+                .unwrap()
+                .with_end_time(start_time - interval),
+        ),
+        // Nothing to combine
+        Box::new(CombinedSchedule::new(vec![])),
+        // Valid leap day schedule
+        Box::new(CronSchedule::new().month(2).unwrap().day(29).unwrap()),
+        // Repeating zero times, around a misconfigured schedule
+        Box::new(RepeatNSchedule::new(
+            Box::new(
+                IntervalSchedule::new(interval, start_time)
+                    .unwrap()
+                    .with_end_time(start_time - interval),
+            ),
+            0,
+        )),
+        // The misconfigured schedule behind filters
+        Box::new(
+            IntervalSchedule::new(interval, start_time)
+                .unwrap()
+                .with_end_time(start_time - interval)
+                .quantize(interval)
+                .unwrap(),
+        ),
+    ];
+
+    // Each failure carries the schedule's own error
+    let failures = validate_all(&schedules);
+    assert_eq!(failures.len(), 5);
+    assert!(matches!(failures[0], (1, SchedulerError::Unsatisfiable)));
+    assert!(matches!(
+        failures[1],
+        (2, SchedulerError::InvalidConfiguration)
+    ));
+    assert!(matches!(
+        failures[2],
+        (3, SchedulerError::InvalidConfiguration)
+    ));
+    assert!(matches!(
+        failures[3],
+        (5, SchedulerError::InvalidRepetition)
+    ));
+    assert!(matches!(
+        failures[4],
+        (6, SchedulerError::InvalidConfiguration)
+    ));
+}
+
+#[test]
+fn test_custom_event_schedule() {
+    // Precomputed full moons, looked up by the closure
+    let full_moons = vec![
+        Utc.with_ymd_and_hms(2023, 1, 6, 23, 8, 0).unwrap(),
+        Utc.with_ymd_and_hms(2023, 2, 5, 18, 29, 0).unwrap(),
+        Utc.with_ymd_and_hms(2023, 3, 7, 12, 40, 0).unwrap(),
+    ];
+    let events = full_moons.clone();
+    let schedule = CustomEventSchedule::new(Box::new(move |after| {
+        events.iter().copied().find(|event| *event > after)
+    }));
+
+    let base_date = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(schedule.next_occurrence(base_date), Some(full_moons[0]));
+    assert_eq!(schedule.next_occurrence(full_moons[0]), Some(full_moons[1]));
+    assert_eq!(schedule.next_occurrence(full_moons[1]), Some(full_moons[2]));
+    assert_eq!(schedule.next_occurrence(full_moons[2]), None);
+
+    // Events that don't move forward are ignored
+    let stuck = CustomEventSchedule::new(Box::new(move |_| Some(base_date)));
+    assert_eq!(stuck.next_occurrence(base_date), None);
+}
+
+#[test]
+fn test_first_occurrence() {
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+
+    // Deterministic schedules match next_occurrence
+    let interval = IntervalSchedule::new(Duration::from_secs(3600), start_time).unwrap();
+    assert_eq!(
+        interval.first_occurrence(start_time - Duration::from_secs(1)),
+        Some(start_time)
+    );
+    assert_eq!(
+        interval.first_occurrence(start_time),
+        interval.next_occurrence(start_time)
+    );
+
+    let cron = CronSchedule::new().hour(12).unwrap().minute(0).unwrap();
+    assert_eq!(
+        cron.first_occurrence(start_time),
+        Some(Utc.with_ymd_and_hms(2023, 1, 1, 12, 0, 0).unwrap())
+    );
+
+    // Seeded random schedules always report the same first draw
+    let min = Duration::from_secs(60);
+    let max = Duration::from_secs(3600);
+    let random = RandomIntervalSchedule::new(min, max)
+        .unwrap()
+        .with_start_time(start_time)
+        .with_seed(42);
+    let first = random.first_occurrence(start_time).unwrap();
+    assert!(first >= start_time + min && first <= start_time + max);
+
+    // Drawing from the sequence doesn't change the first occurrence
+    random.next_occurrence(start_time);
+    random.next_occurrence(start_time);
+    assert_eq!(random.first_occurrence(start_time), Some(first));
+
+    // A fresh schedule with the same seed draws the same first occurrence
+    let replay = RandomIntervalSchedule::new(min, max)
+        .unwrap()
+        .with_start_time(start_time)
+        .with_seed(42);
+    assert_eq!(replay.next_occurrence(start_time), Some(first));
+}
+
+#[test]
+fn test_deadline_compression() {
+    // 4 hourly runs that must all land before the deadline
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let deadline = start_time + chrono::TimeDelta::hours(4);
+    let interval = Duration::from_secs(3600);
+    let schedule = IntervalSchedule::new(interval, start_time).unwrap();
+
+    let mut job = Job::builder()
+        .schedule(Box::new(schedule))
+        .task("Deadline task")
+        .build()
+        .unwrap()
+        .with_deadline_and_count(deadline, 4);
+
+    // On schedule the job follows the hourly cadence
+    assert!(job.should_execute(start_time).is_some());
+    assert!(job
+        .should_execute(start_time + chrono::TimeDelta::minutes(30))
+        .is_none());
+
+    // After missing the 1:00 and 2:00 runs, the job catches up straight away
+    let delayed = start_time + chrono::TimeDelta::minutes(150);
+    assert!(job.should_execute(delayed).is_some());
+    assert_eq!(job.repeats, 2);
+
+    // The remaining 2 runs now share the 90 minutes left, every 30 minutes
+    assert!(job
+        .should_execute(delayed + chrono::TimeDelta::minutes(20))
+        .is_none());
+    let third = delayed + chrono::TimeDelta::minutes(30);
+    assert!(job.should_execute(third).is_some());
+    let fourth = third + chrono::TimeDelta::minutes(30);
+    assert!(job.should_execute(fourth).is_some());
+    assert!(fourth < deadline);
+    assert_eq!(job.repeats, 4);
+
+    // All runs are done before the deadline
+    assert!(job
+        .should_execute(deadline - Duration::from_secs(1))
+        .is_none());
+
+    // A count too large to space out just follows the schedule
+    let mut job = Job::builder()
+        .schedule(IntervalSchedule::new(interval, start_time).unwrap())
+        .task("Unbounded task")
+        .build()
+        .unwrap()
+        .with_deadline_and_count(deadline, u32::MAX);
+    assert!(job.should_execute(start_time).is_some());
+    assert!(job.should_execute(delayed).is_none());
+    assert!(job
+        .should_execute(start_time + chrono::TimeDelta::hours(3))
+        .is_some());
+}
+
+#[test]
+fn test_job_display() {
+    let start_time = Utc.with_ymd_and_hms(2100, 1, 1, 0, 0, 0).unwrap();
+    let schedule = IntervalSchedule::new(Duration::from_secs(3600), start_time).unwrap();
+
+    let mut job = Job::builder()
+        .schedule(Box::new(schedule))
+        .task("Hourly report")
+        .max_repeats(2)
+        .build()
+        .unwrap();
+
+    // A new job already shows when it first fires
+    assert_eq!(
+        job.to_string(),
+        "Hourly report (every 1h from 2100-01-01 00:00:00 UTC), ran 0 of 2 times, \
+         next at 2100-01-01 00:00:00 UTC"
+    );
+
+    job.should_execute(start_time);
+    assert_eq!(
+        job.to_string(),
+        "Hourly report (every 1h from 2100-01-01 00:00:00 UTC), ran 1 of 2 times, \
+         next at 2100-01-01 01:00:00 UTC"
+    );
+
+    job.should_execute(start_time + Duration::from_secs(3600));
+    assert_eq!(
+        job.to_string(),
+        "Hourly report (every 1h from 2100-01-01 00:00:00 UTC), ran 2 of 2 times, finished"
+    );
+
+    // Showing a capped job before its first poll doesn't move its count
+    let hourly = CronSchedule::new().minute(0).unwrap();
+    let mut job = Job::recurring(RepeatNSchedule::new(Box::new(hourly), 1))
+        .task("Capped report")
+        .build()
+        .unwrap();
+    assert!(job.to_string().contains(", next at "));
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    assert!(job.should_execute(start).is_some());
+
+    // Cron schedules render as a standard cron expression
+    let cron = CronSchedule::new()
+        .weekday(0)
+        .unwrap()
+        .hour(9)
+        .unwrap()
+        .minute(30)
+        .unwrap();
+    assert_eq!(cron.describe(), "cron 30 9 * * 1");
+}
+
+#[test]
+fn test_lead_by() {
+    let base_date = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let lead = Duration::from_secs(5 * 60);
+
+    // Pre-warm 5 minutes before the daily 9am job
+    let daily = CronSchedule::new().hour(9).unwrap().minute(0).unwrap();
+    let warm_up = CronSchedule::new()
+        .hour(9)
+        .unwrap()
+        .minute(0)
+        .unwrap()
+        .lead_by(lead);
+
+    let mut after = base_date;
+    for _ in 0..3 {
+        let inner = daily.next_occurrence(after + lead).unwrap();
+        let fire = warm_up.next_occurrence(after).unwrap();
+        assert_eq!(fire, inner - lead);
+        assert_eq!(fire.hour(), 8);
+        assert_eq!(fire.minute(), 55);
+        after = fire;
+    }
+
+    assert_eq!(
+        warm_up.next_occurrence(base_date),
+        Some(Utc.with_ymd_and_hms(2023, 1, 1, 8, 55, 0).unwrap())
+    );
+    assert_eq!(
+        warm_up.next_occurrence(Utc.with_ymd_and_hms(2023, 1, 1, 8, 55, 0).unwrap()),
+        Some(Utc.with_ymd_and_hms(2023, 1, 2, 8, 55, 0).unwrap())
+    );
+}
+
+#[test]
+fn test_cron_schedule_does_not_repeat_within_matched_minute() {
+    let schedule = CronSchedule::new().hour(12).unwrap().minute(0).unwrap();
+    let noon = Utc.with_ymd_and_hms(2023, 1, 1, 12, 0, 0).unwrap();
+
+    assert_eq!(
+        schedule.next_occurrence(noon),
+        Some(Utc.with_ymd_and_hms(2023, 1, 2, 12, 0, 0).unwrap())
+    );
+
+    let mut job = Job::builder()
+        .schedule(Box::new(schedule))
+        .task("Noon task")
+        .build()
+        .unwrap();
+    assert!(job.should_execute(noon).is_some());
+    assert!(job.should_execute(noon + Duration::from_secs(30)).is_none());
+}
+
+#[test]
+fn test_quantize_random_schedule() {
+    let base_date = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let five_minutes = Duration::from_secs(5 * 60);
+
+    let schedule = RandomIntervalSchedule::new(Duration::from_secs(60), Duration::from_secs(1800))
+        .unwrap()
+        .with_seed(7)
+        .quantize(five_minutes)
+        .unwrap();
+
+    let mut after = base_date;
+    for _ in 0..20 {
+        let next = schedule.next_occurrence(after).unwrap();
+        assert!(next > after, "Quantized occurrence must stay after {after}");
+        assert_eq!(next.timestamp() % 300, 0);
+        after = next;
+    }
+
+    // Drawing from a fixed start, the inner schedule answers the same way whatever it is asked,
+    // so an occurrence rounding back to or before `after` is drawn again rather than ending it
+    let from_start = || {
+        RandomIntervalSchedule::new(Duration::from_secs(60), Duration::from_secs(1800))
+            .unwrap()
+            .with_seed(0)
+            .with_start_time(base_date)
+            .quantize(five_minutes)
+            .unwrap()
+    };
+
+    let schedule = from_start();
+    for minute in 0..30 {
+        let after = base_date + chrono::TimeDelta::minutes(minute);
+        if let Some(next) = schedule.next_occurrence(after) {
+            assert!(next > after, "Quantized occurrence must stay after {after}");
+            assert_eq!(next.timestamp() % 300, 0);
+        }
+    }
+
+    let mut job = Job::recurring(from_start()).task(()).build().unwrap();
+    let fired = (0..30)
+        .map(|minute| base_date + chrono::TimeDelta::minutes(minute))
+        .filter(|now| job.should_execute(*now).is_some())
+        .count();
+    assert!(fired > 1, "Job stopped after {fired} fire(s)");
+
+    // A zero grid is rejected
+    let result = IntervalSchedule::new(Duration::from_secs(60), base_date)
+        .unwrap()
+        .quantize(Duration::from_secs(0));
+    assert!(matches!(result, Err(SchedulerError::InvalidDuration)));
+}
+
+#[test]
+fn test_cron_sparse_schedule_step_count() {
+    // Once a year, on New Year's Eve just before midnight
+    let yearly = CronSchedule::new()
+        .month(12)
+        .unwrap()
+        .day(31)
+        .unwrap()
+        .hour(23)
+        .unwrap()
+        .minute(59)
+        .unwrap();
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+
+    let (next, steps) = yearly.next_occurrence_with_steps(start);
+    assert_eq!(
+        next,
+        Some(Utc.with_ymd_and_hms(2023, 12, 31, 23, 59, 0).unwrap())
+    );
+    assert!(steps < 10, "Yearly cron took {steps} steps");
+
+    let (next, steps) = yearly.next_occurrence_with_steps(next.unwrap());
+    assert_eq!(
+        next,
+        Some(Utc.with_ymd_and_hms(2024, 12, 31, 23, 59, 0).unwrap())
+    );
+    assert!(steps < 10, "Yearly cron took {steps} steps");
+
+    // Leap day falling on a Monday only comes around every 28 years
+    let leap_monday = CronSchedule::new()
+        .month(2)
+        .unwrap()
+        .day(29)
+        .unwrap()
+        .weekday(0)
+        .unwrap()
+        .hour(0)
+        .unwrap()
+        .minute(0)
+        .unwrap();
+    let start = Utc.with_ymd_and_hms(2017, 1, 1, 0, 0, 0).unwrap();
+
+    let (next, steps) = leap_monday.next_occurrence_with_steps(start);
+    assert_eq!(
+        next,
+        Some(Utc.with_ymd_and_hms(2044, 2, 29, 0, 0, 0).unwrap())
+    );
+    assert!(steps < 300, "Leap day cron took {steps} steps");
+
+    // December day rollover no longer panics
+    let mid_month = CronSchedule::new()
+        .day(15)
+        .unwrap()
+        .hour(0)
+        .unwrap()
+        .minute(0)
+        .unwrap();
+    let late_december = Utc.with_ymd_and_hms(2023, 12, 20, 0, 0, 0).unwrap();
+    assert_eq!(
+        mid_month.next_occurrence(late_december),
+        Some(Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap())
+    );
+
+    // Days missing from short months are skipped
+    let thirty_first = CronSchedule::new()
+        .day(31)
+        .unwrap()
+        .hour(0)
+        .unwrap()
+        .minute(0)
+        .unwrap();
+    let april = Utc.with_ymd_and_hms(2023, 4, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        thirty_first.next_occurrence(april),
+        Some(Utc.with_ymd_and_hms(2023, 5, 31, 0, 0, 0).unwrap())
+    );
+}
+
+#[test]
+fn test_cron_schedule_at_end_of_time() {
+    let last = DateTime::<Utc>::MAX_UTC;
+
+    // Searching past the last representable time finds nothing rather than overflowing
+    let every_minute = CronSchedule::new();
+    assert_eq!(every_minute.next_occurrence(last), None);
+    assert_eq!(
+        every_minute.next_occurrence(last - chrono::TimeDelta::minutes(1)),
+        Some(last.with_second(0).unwrap().with_nanosecond(0).unwrap())
+    );
+
+    let on_the_hour = CronSchedule::new().minute(0).unwrap();
+    assert_eq!(
+        on_the_hour.next_occurrence(last - chrono::TimeDelta::minutes(5)),
+        None
+    );
+
+    let at_midnight = CronSchedule::new().hour(0).unwrap().minute(0).unwrap();
+    assert_eq!(
+        at_midnight.next_occurrence(last - chrono::TimeDelta::hours(2)),
+        None
+    );
+
+    let tomorrow = last.weekday().succ().num_days_from_monday();
+    let weekly = CronSchedule::new().weekday(tomorrow).unwrap();
+    assert_eq!(
+        weekly.next_occurrence(last - chrono::TimeDelta::hours(2)),
+        None
+    );
+}
+
+#[test]
+fn test_job_occurrence_iter() {
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let interval = Duration::from_secs(3600);
+    let schedule = IntervalSchedule::new(interval, start_time).unwrap();
+
+    let job = Job::builder()
+        .schedule(Box::new(schedule))
+        .task("Hourly task")
+        .max_repeats(4)
+        .build()
+        .unwrap();
+
+    let occurrences: Vec<_> = job.into_occurrence_iter(start_time).collect();
+    assert_eq!(
+        occurrences,
+        (0..4)
+            .map(|i| start_time + interval * i)
+            .collect::<Vec<_>>()
+    );
+
+    // Runs already made count against max_repeats, and end_time cuts the sequence short
+    let schedule = IntervalSchedule::new(interval, start_time).unwrap();
+    let mut job = Job::builder()
+        .schedule(Box::new(schedule))
+        .task("Hourly task")
+        .max_repeats(4)
+        .end_time(start_time + interval * 10)
+        .build()
+        .unwrap();
+    assert!(job.should_execute(start_time).is_some());
+    assert_eq!(job.into_occurrence_iter(start_time + interval).count(), 3);
+
+    let schedule = IntervalSchedule::new(interval, start_time).unwrap();
+    let job = Job::builder()
+        .schedule(Box::new(schedule))
+        .task("Hourly task")
+        .end_time(start_time + interval * 2)
+        .build()
+        .unwrap();
+    assert_eq!(job.into_occurrence_iter(start_time).count(), 2);
+}
+
+#[test]
+fn test_schedule_diff() {
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let end_time = start_time + chrono::TimeDelta::days(1);
+    let hourly = IntervalSchedule::new(Duration::from_secs(3600), start_time).unwrap();
+    let two_hourly = IntervalSchedule::new(Duration::from_secs(7200), start_time).unwrap();
+
+    // Moving from hourly to every two hours drops the odd hours
+    let (added, removed) = schedule_diff(&hourly, &two_hourly, start_time..end_time);
+    assert!(added.is_empty());
+    assert_eq!(
+        removed,
+        (0..12)
+            .map(|i| start_time + chrono::TimeDelta::hours(2 * i + 1))
+            .collect::<Vec<_>>()
+    );
+
+    // The reverse change adds them back
+    let (added, removed) = schedule_diff(&two_hourly, &hourly, start_time..end_time);
+    assert_eq!(added.len(), 12);
+    assert!(removed.is_empty());
+}
+
+#[test]
+fn test_filtered_interval_skips_weekends() {
+    // Every 2 hours, only on weekdays. Friday, March 3rd 2023, 8pm
+    let start_time = Utc.with_ymd_and_hms(2023, 3, 3, 20, 0, 0).unwrap();
+    let weekdays = vec![
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+    ];
+    let schedule =
+        FilteredIntervalSchedule::new(Duration::from_secs(7200), start_time, weekdays).unwrap();
+
+    assert_eq!(
+        schedule.next_occurrence(start_time),
+        Some(Utc.with_ymd_and_hms(2023, 3, 3, 22, 0, 0).unwrap())
+    );
+
+    // The whole weekend grid is skipped, resuming at Monday midnight
+    let friday_10pm = Utc.with_ymd_and_hms(2023, 3, 3, 22, 0, 0).unwrap();
+    let monday_midnight = Utc.with_ymd_and_hms(2023, 3, 6, 0, 0, 0).unwrap();
+    assert_eq!(schedule.next_occurrence(friday_10pm), Some(monday_midnight));
+
+    let saturday_noon = Utc.with_ymd_and_hms(2023, 3, 4, 12, 0, 0).unwrap();
+    assert_eq!(
+        schedule.next_occurrence(saturday_noon),
+        Some(monday_midnight)
+    );
+
+    assert_eq!(
+        schedule.next_occurrence(monday_midnight),
+        Some(Utc.with_ymd_and_hms(2023, 3, 6, 2, 0, 0).unwrap())
+    );
+
+    // A weekly grid that never lands on an allowed day gives up
+    let saturdays_only = FilteredIntervalSchedule::new(
+        Duration::from_secs(7 * 86400),
+        start_time,
+        vec![Weekday::Sat],
+    )
+    .unwrap();
+    assert_eq!(saturdays_only.next_occurrence(start_time), None);
+
+    let result = FilteredIntervalSchedule::new(Duration::from_secs(3600), start_time, vec![]);
+    assert!(matches!(result, Err(SchedulerError::InvalidConfiguration)));
+
+    // Fractional intervals keep their milliseconds
+    let schedule =
+        FilteredIntervalSchedule::new(Duration::from_millis(1500), start_time, vec![Weekday::Fri])
+            .unwrap();
+    let step = chrono::TimeDelta::milliseconds(1500);
+    assert_eq!(
+        schedule.occurrences_limited(start_time, 2, friday_10pm),
+        vec![start_time + step, start_time + step * 2]
+    );
+}
+
+#[test]
+fn test_metrics_snapshot() {
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let interval = Duration::from_secs(3600);
+    let end_time = start_time + interval * 3;
+    let schedule = IntervalSchedule::new(interval, start_time)
+        .unwrap()
+        .with_end_time(end_time);
+
+    // Mid-run at 1:15, the 0:00 and 1:00 fires are done
+    let now = start_time + Duration::from_secs(4500);
+    assert_eq!(
+        schedule.metrics_snapshot(now),
+        MetricsSnapshot {
+            seconds_until_next: Some(2700),
+            total_fires: 2,
+            finished: false,
+        }
+    );
+
+    // After the end time all 4 grid points have fired
+    assert_eq!(
+        schedule.metrics_snapshot(end_time + interval),
+        MetricsSnapshot {
+            seconds_until_next: None,
+            total_fires: 4,
+            finished: true,
+        }
+    );
+
+    // With 1.5s steps, 0s, 1.5s and 3s have fired by 4s
+    let fractional = IntervalSchedule::new(Duration::from_millis(1500), start_time).unwrap();
+    assert_eq!(
+        fractional
+            .metrics_snapshot(start_time + Duration::from_secs(4))
+            .total_fires,
+        3
+    );
+
+    let mut job = Job::builder()
+        .schedule(Box::new(schedule))
+        .task("Hourly task")
+        .max_repeats(2)
+        .build()
+        .unwrap();
+    job.should_execute(start_time);
+    assert_eq!(
+        job.metrics_snapshot(start_time + Duration::from_secs(1800)),
+        MetricsSnapshot {
+            seconds_until_next: Some(1800),
+            total_fires: 1,
+            finished: false,
+        }
+    );
+
+    job.should_execute(start_time + interval);
+    assert_eq!(
+        job.metrics_snapshot(start_time + interval),
+        MetricsSnapshot {
+            seconds_until_next: None,
+            total_fires: 2,
+            finished: true,
+        }
+    );
+}
+
+#[test]
+fn test_cron_day_step_restarts_each_month() {
+    // Every 3rd day of the month at midnight: 1, 4, 7, ..., 28, 31
+    let schedule = CronSchedule::new()
+        .day_step(3)
+        .unwrap()
+        .hour(0)
+        .unwrap()
+        .minute(0)
+        .unwrap();
+
+    let jan_28 = Utc.with_ymd_and_hms(2023, 1, 28, 0, 0, 0).unwrap();
+    let jan_31 = Utc.with_ymd_and_hms(2023, 1, 31, 0, 0, 0).unwrap();
+    let feb_1 = Utc.with_ymd_and_hms(2023, 2, 1, 0, 0, 0).unwrap();
+    let feb_4 = Utc.with_ymd_and_hms(2023, 2, 4, 0, 0, 0).unwrap();
+
+    assert_eq!(schedule.next_occurrence(jan_28), Some(jan_31));
+    // The step restarts on the 1st rather than continuing from the 31st
+    assert_eq!(schedule.next_occurrence(jan_31), Some(feb_1));
+    assert_eq!(schedule.next_occurrence(feb_1), Some(feb_4));
+
+    // February's last stepped day is the 28th, then March starts over
+    let feb_28 = Utc.with_ymd_and_hms(2023, 2, 28, 0, 0, 0).unwrap();
+    assert_eq!(
+        schedule.next_occurrence(Utc.with_ymd_and_hms(2023, 2, 26, 0, 0, 0).unwrap()),
+        Some(feb_28)
+    );
+    assert_eq!(
+        schedule.next_occurrence(feb_28),
+        Some(Utc.with_ymd_and_hms(2023, 3, 1, 0, 0, 0).unwrap())
+    );
+    assert_eq!(schedule.describe(), "cron 0 0 */3 * *");
+
+    assert!(CronSchedule::new().day_step(0).is_err());
+}
+
+#[test]
+fn test_job_plan() {
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let interval = Duration::from_secs(3600);
+    let schedule = IntervalSchedule::new(interval, start_time).unwrap();
+
+    let job = Job::builder()
+        .schedule(Box::new(schedule))
+        .task("Hourly export")
+        .label("reports")
+        .label("nightly")
+        .build()
+        .unwrap();
+
+    let plan = job.to_plan(start_time, interval * 3);
+    assert_eq!(plan.labels, vec!["reports", "nightly"]);
+    assert_eq!(plan.schedule, "every 1h from 2023-01-01 00:00:00 UTC");
+    assert_eq!(
+        plan.fire_times,
+        vec![start_time, start_time + interval, start_time + interval * 2]
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_job_plan_serialization() {
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let interval = Duration::from_secs(3600);
+    let schedule = IntervalSchedule::new(interval, start_time).unwrap();
+
+    let job = Job::builder()
+        .schedule(Box::new(schedule))
+        .task("Hourly export")
+        .label("reports")
+        .build()
+        .unwrap();
+
+    let plan = job.to_plan(start_time, interval * 3);
+    let json = serde_json::to_string(&plan).unwrap();
+    assert!(json.contains("\"labels\":[\"reports\"]"));
+    assert_eq!(serde_json::from_str::<JobPlan>(&json).unwrap(), plan);
+}
+
+#[test]
+fn test_gated_by_feature_flag() {
+    use std::{cell::Cell, rc::Rc};
+
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let interval = Duration::from_secs(3600);
+    let flag = Rc::new(Cell::new(true));
+    let enabled = Rc::clone(&flag);
+    let schedule = IntervalSchedule::new(interval, start_time)
+        .unwrap()
+        .gated_by(Box::new(move || enabled.get()));
+
+    let mut job = Job::builder()
+        .schedule(Box::new(schedule))
+        .task("Rollout task")
+        .build()
+        .unwrap();
+
+    assert!(job.should_execute(start_time).is_some());
+
+    // Disabled: the 1:00 and 2:00 occurrences are suppressed
+    flag.set(false);
+    assert!(job.should_execute(start_time + interval).is_none());
+    assert!(job.should_execute(start_time + interval * 2).is_none());
+
+    // Re-enabled: fires resume on the next occurrence
+    flag.set(true);
+    assert!(job.should_execute(start_time + interval * 3).is_some());
+    assert_eq!(job.repeats, 2);
+}
+
+#[test]
+fn test_daily_times_schedule() {
+    let schedule = DailyTimesSchedule::parse("13:30, 09:00,17:45").unwrap();
+    let morning = Utc.with_ymd_and_hms(2023, 1, 1, 8, 0, 0).unwrap();
+
+    let nine_am = Utc.with_ymd_and_hms(2023, 1, 1, 9, 0, 0).unwrap();
+    let half_one = Utc.with_ymd_and_hms(2023, 1, 1, 13, 30, 0).unwrap();
+    let quarter_to_six = Utc.with_ymd_and_hms(2023, 1, 1, 17, 45, 0).unwrap();
+    let next_nine_am = Utc.with_ymd_and_hms(2023, 1, 2, 9, 0, 0).unwrap();
+
+    assert_eq!(schedule.next_occurrence(morning), Some(nine_am));
+    assert_eq!(schedule.next_occurrence(nine_am), Some(half_one));
+    assert_eq!(schedule.next_occurrence(half_one), Some(quarter_to_six));
+    assert_eq!(schedule.next_occurrence(quarter_to_six), Some(next_nine_am));
+    assert_eq!(schedule.describe(), "daily at 09:00, 13:30, 17:45");
+}
+
+#[test]
+fn test_daily_times_schedule_malformed() {
+    for times in ["", "25:00", "9am", "09:00,,10:00", "12:60", "09:00;10:00"] {
+        assert!(
+            matches!(
+                DailyTimesSchedule::parse(times),
+                Err(SchedulerError::InvalidDateTime)
+            ),
+            "{times:?} should be rejected"
+        );
+    }
+}
+
+#[test]
+fn test_max_gap() {
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let end_time = start_time + chrono::TimeDelta::days(1);
+
+    let hourly = IntervalSchedule::new(Duration::from_secs(3600), start_time).unwrap();
+    assert_eq!(
+        hourly.max_gap(start_time, end_time),
+        Some(Duration::from_secs(3600))
+    );
+
+    // The lead-in from the window start counts as a gap
+    let late_start = IntervalSchedule::new(
+        Duration::from_secs(3600),
+        start_time + chrono::TimeDelta::hours(5),
+    )
+    .unwrap();
+    assert_eq!(
+        late_start.max_gap(start_time, end_time),
+        Some(Duration::from_secs(5 * 3600))
+    );
+
+    // Weekdays at 9am: the weekend leaves a gap from Friday to Monday
+    let weekday_mornings = CombinedSchedule::new(
+        (0..5)
+            .map(|weekday| {
+                Box::new(
+                    CronSchedule::new()
+                        .weekday(weekday)
+                        .unwrap()
+                        .hour(9)
+                        .unwrap()
+                        .minute(0)
+                        .unwrap(),
+                ) as Box<dyn Schedule>
+            })
+            .collect(),
+    );
+    // Monday, January 2nd 2023 for two weeks
+    let monday = Utc.with_ymd_and_hms(2023, 1, 2, 9, 0, 0).unwrap();
+    assert_eq!(
+        weekday_mornings.max_gap(monday, monday + chrono::TimeDelta::weeks(2)),
+        Some(Duration::from_secs(72 * 3600))
+    );
+
+    // No occurrences in the window
+    let one_time = OneTimeSchedule::new(Utc::now() + Duration::from_secs(3600)).unwrap();
+    assert_eq!(one_time.max_gap(start_time, end_time), None);
+}
+
+#[test]
+fn test_occurrences_limited() {
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let interval = Duration::from_secs(3600);
+    let hourly = IntervalSchedule::new(interval, start_time).unwrap();
+
+    // The count binds first
+    let until = start_time + chrono::TimeDelta::days(1);
+    assert_eq!(
+        hourly.occurrences_limited(start_time, 3, until),
+        vec![
+            start_time + interval,
+            start_time + interval * 2,
+            start_time + interval * 3
+        ]
+    );
+
+    // The end binds first, inclusive of an occurrence exactly at `until`
+    let until = start_time + interval * 2;
+    assert_eq!(
+        hourly.occurrences_limited(start_time, 10, until),
+        vec![start_time + interval, start_time + interval * 2]
+    );
+
+    assert!(hourly.occurrences_limited(start_time, 0, until).is_empty());
+}
+
+#[test]
+fn test_one_time_immediate() {
+    let mut job = Job::builder()
+        .schedule(Box::new(OneTimeSchedule::immediate()))
+        .task("Initialization task")
+        .build()
+        .unwrap();
+
+    // Fires on the very first poll, then never again
+    let now = Utc::now();
+    assert!(job.should_execute(now).is_some());
+    assert!(job.should_execute(now).is_none());
+    assert!(job
+        .should_execute(now + Duration::from_secs(3600))
+        .is_none());
+    assert_eq!(job.repeats, 1);
+
+    // However long after creation the first poll comes
+    let mut job = Job::builder()
+        .schedule(OneTimeSchedule::immediate())
+        .task("Late task")
+        .build()
+        .unwrap();
+    let first_poll = Utc::now() + chrono::TimeDelta::minutes(1);
+    let fired: Vec<_> = (0..120)
+        .map(|second| first_poll + chrono::TimeDelta::seconds(second))
+        .filter(|time| job.should_execute(*time).is_some())
+        .collect();
+    assert_eq!(fired, vec![first_poll]);
+    assert!(job.is_finished(first_poll));
+
+    // A plan shows the fire where it starts, without using it up
+    let mut job = Job::builder()
+        .schedule(Box::new(OneTimeSchedule::immediate()))
+        .task("Previewed task")
+        .build()
+        .unwrap();
+    let later = Utc::now() + chrono::TimeDelta::hours(1);
+    assert_eq!(
+        job.to_plan(later, Duration::from_secs(60)).fire_times,
+        vec![later]
+    );
+    assert!(!job.is_finished(later));
+    let now = Utc::now();
+    assert!(job.should_execute(now).is_some());
+    assert!(job.should_execute(now).is_none());
+    assert!(job
+        .to_plan(now, Duration::from_secs(3600))
+        .fire_times
+        .is_empty());
+    // The job keeps when it fired
+    assert_eq!(job.last_fired(), Some(now));
+}
+
+#[test]
+fn test_job_never_runs_an_occurrence_twice() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let mut job = Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap())
+        .task(())
+        .build()
+        .unwrap();
+
+    // Still due half a second late, but the second poll in that second finds it already run
+    let late = start + chrono::TimeDelta::milliseconds(500);
+    assert!(job.should_execute(start).is_some());
+    assert!(job.should_execute(late).is_none());
+    assert!(job
+        .should_execute(start + chrono::TimeDelta::hours(1))
+        .is_some());
+    assert_eq!(job.repeats, 2);
+}
+
+#[test]
+fn test_cron_minute_rollover_at_end_of_day() {
+    let schedule = CronSchedule::new().minute(30).unwrap();
+
+    let late_evening = Utc.with_ymd_and_hms(2023, 3, 1, 23, 59, 59).unwrap();
+    assert_eq!(
+        schedule.next_occurrence(late_evening),
+        Some(Utc.with_ymd_and_hms(2023, 3, 2, 0, 30, 0).unwrap())
+    );
+
+    let end_of_month = Utc.with_ymd_and_hms(2023, 4, 30, 23, 45, 0).unwrap();
+    assert_eq!(
+        schedule.next_occurrence(end_of_month),
+        Some(Utc.with_ymd_and_hms(2023, 5, 1, 0, 30, 0).unwrap())
+    );
+
+    let end_of_year = Utc.with_ymd_and_hms(2023, 12, 31, 23, 45, 0).unwrap();
+    assert_eq!(
+        schedule.next_occurrence(end_of_year),
+        Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 30, 0).unwrap())
+    );
+}
+
+#[test]
+fn test_schedule_matches() {
+    let minute = Duration::from_secs(60);
+
+    // Cron fields are checked directly
+    let cron = CronSchedule::new().hour(9).unwrap().minute(0).unwrap();
+    let nine_am = Utc.with_ymd_and_hms(2023, 1, 1, 9, 0, 0).unwrap();
+    assert!(cron.matches(nine_am, Duration::ZERO));
+    assert!(!cron.matches(nine_am + Duration::from_secs(30), Duration::ZERO));
+    assert!(cron.matches(nine_am + Duration::from_secs(30), minute));
+    assert!(cron.matches(nine_am - Duration::from_secs(30), minute));
+    assert!(!cron.matches(nine_am + minute * 5, minute));
+    assert!(!cron.matches(nine_am + chrono::TimeDelta::hours(1), Duration::ZERO));
+
+    // Interval occurrences must line up with the grid
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let interval = Duration::from_secs(3600);
+    let hourly = IntervalSchedule::new(interval, start_time)
+        .unwrap()
+        .with_end_time(start_time + interval * 3);
+    assert!(hourly.matches(start_time, Duration::ZERO));
+    assert!(hourly.matches(start_time + interval * 2, Duration::ZERO));
+    assert!(hourly.matches(start_time + interval * 2 + Duration::from_secs(30), minute));
+    assert!(hourly.matches(start_time + interval * 2 - Duration::from_secs(30), minute));
+    assert!(!hourly.matches(start_time + interval * 2 + Duration::from_secs(90), minute));
+    assert!(!hourly.matches(start_time + Duration::from_secs(1800), minute));
+    assert!(hourly.matches(start_time - Duration::from_secs(30), minute));
+    // Past the end time nothing matches
+    assert!(!hourly.matches(start_time + interval * 4, minute));
+
+    // Other schedules search for an occurrence around the time
+    let daily_times = DailyTimesSchedule::parse("09:00").unwrap();
+    assert!(daily_times.matches(nine_am, Duration::ZERO));
+    assert!(daily_times.matches(nine_am + Duration::from_secs(30), minute));
+    assert!(!daily_times.matches(nine_am + minute * 2, minute));
+}
+
+#[test]
+fn test_combined_schedule_global_max() {
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let combined = || {
+        CombinedSchedule::new(vec![
+            Box::new(IntervalSchedule::new(Duration::from_secs(3600), start_time).unwrap()),
+            Box::new(IntervalSchedule::new(Duration::from_secs(5400), start_time).unwrap()),
+        ])
+        .with_global_max(5)
+    };
+
+    // 1:00, 1:30, 2:00, 3:00 (shared) and 4:00, then the cap is reached
+    let schedule = combined();
+    let mut after = start_time;
+    let mut fires = Vec::new();
+    while let Some(next) = schedule.next_occurrence(after) {
+        schedule.record_fire(next);
+        fires.push(next);
+        after = next;
+    }
+    assert_eq!(
+        fires,
+        vec![
+            start_time + chrono::TimeDelta::minutes(60),
+            start_time + chrono::TimeDelta::minutes(90),
+            start_time + chrono::TimeDelta::minutes(120),
+            start_time + chrono::TimeDelta::minutes(180),
+            start_time + chrono::TimeDelta::minutes(240),
+        ]
+    );
+
+    // Polling a job in between fires doesn't use up the cap
+    let mut job = Job::builder()
+        .schedule(Box::new(combined()))
+        .task("Capped task")
+        .build()
+        .unwrap();
+    let mut total_fires = 0;
+    for minute in (0..=600).step_by(15) {
+        if job
+            .should_execute(start_time + chrono::TimeDelta::minutes(minute))
+            .is_some()
+        {
+            total_fires += 1;
+        }
+    }
+    assert_eq!(total_fires, 5);
+
+    // Looking ahead and then polling from an earlier time still stops at the cap
+    let capped = CombinedSchedule::new(vec![Box::new(
+        IntervalSchedule::new(Duration::from_secs(3600), start_time).unwrap(),
+    )])
+    .with_global_max(3);
+    let later = start_time + chrono::TimeDelta::hours(2);
+    assert_eq!(capped.preview(later, 10).len(), 3);
+    let mut job = Job::builder()
+        .schedule(Box::new(capped))
+        .task("Previewed task")
+        .build()
+        .unwrap();
+    let mut total_fires = 0;
+    for minute in (0..=600).step_by(15) {
+        if job
+            .should_execute(start_time + chrono::TimeDelta::minutes(minute))
+            .is_some()
+        {
+            total_fires += 1;
+        }
+    }
+    assert_eq!(total_fires, 3);
+
+    // Far off match checks don't change which five times fire
+    let schedule = combined();
+    let far = start_time + chrono::TimeDelta::hours(100);
+    assert!(schedule.matches(far, Duration::ZERO));
+    assert!(schedule.fires_within(far, Duration::from_secs(3600)));
+    let mut job = Job::builder()
+        .schedule(Box::new(schedule))
+        .task("Checked task")
+        .build()
+        .unwrap();
+    let fired: Vec<_> = (0..=600)
+        .step_by(15)
+        .map(|minute| start_time + chrono::TimeDelta::minutes(minute))
+        .filter(|time| job.should_execute(*time).is_some())
+        .collect();
+    let minutes = |minutes: &[i64]| -> Vec<_> {
+        minutes
+            .iter()
+            .map(|minute| start_time + chrono::TimeDelta::minutes(*minute))
+            .collect()
+    };
+    assert_eq!(fired, minutes(&[0, 60, 90, 120, 180]));
+
+    // Nor do a job's plan, counts or deadline before its first poll
+    let mut job = Job::builder()
+        .schedule(combined())
+        .task("Planned task")
+        .build()
+        .unwrap();
+    let far = start_time + chrono::TimeDelta::days(1);
+    assert_eq!(
+        job.to_plan(far, Duration::from_secs(86400))
+            .fire_times
+            .len(),
+        5
+    );
+    assert_eq!(job.count_between(far, far + chrono::TimeDelta::days(1)), 5);
+    assert!(job.deadline_for_next(far).is_some());
+    let fired: Vec<_> = (0..=600)
+        .step_by(15)
+        .map(|minute| start_time + chrono::TimeDelta::minutes(minute))
+        .filter(|time| job.should_execute(*time).is_some())
+        .collect();
+    assert_eq!(fired, minutes(&[0, 60, 90, 120, 180]));
+}
+
+#[test]
+fn test_combined_schedule_global_max_random_children() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let random = |seed| {
+        Box::new(
+            RandomIntervalSchedule::new(Duration::from_secs(1), Duration::from_secs(4))
+                .unwrap()
+                .with_seed(seed),
+        ) as Box<dyn Schedule>
+    };
+
+    // Children drawing afresh on every poll still fire exactly the cap between them
+    for seed in 0..20 {
+        let combined =
+            CombinedSchedule::new(vec![random(seed), random(seed + 100)]).with_global_max(4);
+        let mut job = Job::recurring(combined).task(()).build().unwrap();
+        let fires = (0..300)
+            .map(|second| start + chrono::TimeDelta::seconds(second))
+            .filter(|time| job.should_execute(*time).is_some())
+            .count();
+        assert_eq!(fires, 4);
+        assert!(job.is_finished(start + chrono::TimeDelta::seconds(300)));
+    }
+
+    // A capped child only counts its own fires
+    let hour = |h: i64| start + chrono::TimeDelta::hours(h);
+    let combined = CombinedSchedule::new(vec![
+        Box::new(RepeatNSchedule::new(
+            Box::new(IntervalSchedule::new(Duration::from_secs(7200), start).unwrap()),
+            2,
+        )),
+        Box::new(
+            IntervalSchedule::new(
+                Duration::from_secs(3600),
+                start + chrono::TimeDelta::minutes(30),
+            )
+            .unwrap(),
+        ),
+    ]);
+    let mut job = Job::recurring(combined).task(()).build().unwrap();
+    let fired: Vec<_> = (0..=12 * 60)
+        .step_by(15)
+        .map(|minute| start + chrono::TimeDelta::minutes(minute))
+        .filter(|time| job.should_execute(*time).is_some())
+        .collect();
+    let on_the_hour: Vec<_> = fired
+        .into_iter()
+        .filter(|time| time.minute() == 0)
+        .collect();
+    assert_eq!(on_the_hour, vec![hour(0), hour(2)]);
+}
+
+#[test]
+fn test_random_interval_rng_state_restore() {
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let min = Duration::from_secs(60);
+    let max = Duration::from_secs(3600);
+    let seeded = || {
+        RandomIntervalSchedule::new(min, max)
+            .unwrap()
+            .with_seed(1234)
+    };
+
+    // Uninterrupted run: three draws, snapshot, three more draws
+    let schedule = seeded();
+    assert!(RandomIntervalSchedule::new(min, max)
+        .unwrap()
+        .rng_state()
+        .is_none());
+    for _ in 0..3 {
+        schedule.next_occurrence(start_time);
+    }
+    let state = schedule.rng_state().unwrap();
+    let expected: Vec<_> = (0..3)
+        .map(|_| schedule.next_occurrence(start_time))
+        .collect();
+
+    // Rebuilt after a restart, the restored schedule continues the same sequence
+    let mut restored = seeded();
+    restored.restore_rng_state(state);
+    let resumed: Vec<_> = (0..3)
+        .map(|_| restored.next_occurrence(start_time))
+        .collect();
+    assert_eq!(resumed, expected);
+
+    // Without restoring, the rebuilt schedule starts over from the seed
+    let restarted = seeded();
+    let from_seed: Vec<_> = (0..3)
+        .map(|_| restarted.next_occurrence(start_time))
+        .collect();
+    assert_ne!(from_seed, expected);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_random_interval_rng_state_serialization() {
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let schedule = RandomIntervalSchedule::new(Duration::from_secs(60), Duration::from_secs(3600))
+        .unwrap()
+        .with_seed(99);
+    schedule.next_occurrence(start_time);
+
+    let json = serde_json::to_string(&schedule.rng_state().unwrap()).unwrap();
+    let state: RngState = serde_json::from_str(&json).unwrap();
+    assert_eq!(Some(state), schedule.rng_state());
+}
+
+#[test]
+fn test_fiscal_schedule_april_start() {
+    // UK style fiscal year starting in April
+    let q1 = FiscalSchedule::new(4)
+        .unwrap()
+        .quarter(1)
+        .unwrap()
+        .day_of_quarter(1)
+        .unwrap()
+        .at(9, 0)
+        .unwrap();
+
+    let new_year = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let q1_2023 = Utc.with_ymd_and_hms(2023, 4, 1, 9, 0, 0).unwrap();
+    assert_eq!(q1.next_occurrence(new_year), Some(q1_2023));
+    assert_eq!(
+        q1.next_occurrence(q1_2023),
+        Some(Utc.with_ymd_and_hms(2024, 4, 1, 9, 0, 0).unwrap())
+    );
+
+    // The fourth fiscal quarter falls in the following calendar year
+    let q4 = FiscalSchedule::new(4).unwrap().quarter(4).unwrap();
+    assert_eq!(
+        q4.next_occurrence(q1_2023),
+        Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+    );
+
+    // Every quarter, 10 days in
+    let reports = FiscalSchedule::new(4).unwrap().day_of_quarter(10).unwrap();
+    assert_eq!(
+        reports.next_occurrence(q1_2023),
+        Some(Utc.with_ymd_and_hms(2023, 4, 10, 0, 0, 0).unwrap())
+    );
+    assert_eq!(
+        reports.next_occurrence(Utc.with_ymd_and_hms(2023, 4, 10, 0, 0, 0).unwrap()),
+        Some(Utc.with_ymd_and_hms(2023, 7, 10, 0, 0, 0).unwrap())
+    );
+
+    // Day 91 of January to March only exists in leap years
+    let leap_only = FiscalSchedule::calendar_quarters()
+        .quarter(1)
+        .unwrap()
+        .day_of_quarter(91)
+        .unwrap();
+    assert_eq!(
+        leap_only.next_occurrence(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()),
+        Some(Utc.with_ymd_and_hms(2028, 3, 31, 0, 0, 0).unwrap())
+    );
+
+    assert!(FiscalSchedule::new(13).is_err());
+    assert!(FiscalSchedule::new(4).unwrap().quarter(5).is_err());
+}
+
+#[test]
+fn test_interval_evenly_within_period() {
+    let midnight = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let day = Duration::from_secs(86400);
+    let schedule = IntervalSchedule::evenly_within(day, 4, midnight).unwrap();
+
+    let fires = schedule.occurrences_limited(midnight - Duration::from_secs(1), 5, midnight + day);
+    let expected: Vec<_> = [0, 6, 12, 18, 24]
+        .iter()
+        .map(|hour| midnight + chrono::TimeDelta::hours(*hour))
+        .collect();
+    assert_eq!(fires, expected);
+
+    assert!(matches!(
+        IntervalSchedule::evenly_within(day, 0, midnight),
+        Err(SchedulerError::InvalidRepetition)
+    ));
+}
+
+#[test]
+fn test_combined_schedule_explain_none() {
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let hour = Duration::from_secs(3600);
+    let combined = CombinedSchedule::new(vec![
+        Box::new(
+            IntervalSchedule::new(hour, start_time)
+                .unwrap()
+                .with_end_time(start_time + hour * 2),
+        ),
+        Box::new(
+            IntervalSchedule::new(hour * 24, start_time)
+                .unwrap()
+                .with_end_time(start_time + hour * 48),
+        ),
+    ]);
+
+    // The hourly child fires within the next hour
+    assert_eq!(combined.explain_none(start_time, hour), None);
+
+    // Only the daily child is left, a day away
+    let after_hourly = start_time + hour * 2;
+    assert_eq!(
+        combined.explain_none(after_hourly, hour),
+        Some(CombinedNoneReason::NoneSoonWithin(hour))
+    );
+
+    // Both children have run out
+    assert_eq!(
+        combined.explain_none(start_time + hour * 48, hour),
+        Some(CombinedNoneReason::AllFinished)
+    );
+
+    // Reaching the global cap also finishes the schedule
+    let capped = CombinedSchedule::new(vec![Box::new(
+        IntervalSchedule::new(hour, start_time).unwrap(),
+    )])
+    .with_global_max(1);
+    capped.record_fire(capped.next_occurrence(start_time).unwrap());
+    assert_eq!(
+        capped.explain_none(start_time + hour, hour),
+        Some(CombinedNoneReason::AllFinished)
+    );
+}
+
+#[test]
+fn test_except_schedule_merges_blackouts() {
+    let start_time = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let at = |hour: i64, minute: i64| {
+        start_time + chrono::TimeDelta::hours(hour) + chrono::TimeDelta::minutes(minute)
+    };
+    let hourly = IntervalSchedule::new(Duration::from_secs(3600), start_time).unwrap();
+
+    // Overlapping, adjacent and out of order windows
+    let schedule = ExceptSchedule::new(
+        Box::new(hourly),
+        vec![
+            (at(6, 0), at(7, 0)),
+            (at(2, 30), at(4, 0)),
+            (at(3, 30), at(5, 0)),
+            (at(5, 0), at(5, 30)),
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        schedule.windows(),
+        &[(at(2, 30), at(5, 30)), (at(6, 0), at(7, 0))]
+    );
+
+    // 3:00, 4:00 and 5:00 fall inside the merged span and 6:00 in the next window
+    assert_eq!(schedule.next_occurrence(at(2, 0)), Some(at(7, 0)));
+    assert_eq!(schedule.next_occurrence(at(1, 0)), Some(at(2, 0)));
+    // An occurrence exactly when a window closes still fires
+    assert_eq!(schedule.next_occurrence(at(6, 30)), Some(at(7, 0)));
+
+    // An occurrence in the window's last second is skipped without losing the ones after it
+    let explicit = ExplicitSchedule::new(vec![
+        start_time + chrono::TimeDelta::milliseconds(9500),
+        start_time + chrono::TimeDelta::seconds(20),
+    ])
+    .unwrap();
+    let schedule = ExceptSchedule::new(
+        Box::new(explicit),
+        vec![(start_time, start_time + chrono::TimeDelta::seconds(10))],
+    )
+    .unwrap();
+    assert_eq!(
+        schedule.next_occurrence(start_time),
+        Some(start_time + chrono::TimeDelta::seconds(20))
+    );
+
+    let invalid = ExceptSchedule::new(
+        Box::new(IntervalSchedule::new(Duration::from_secs(3600), start_time).unwrap()),
+        vec![(at(2, 0), at(1, 0))],
+    );
+    assert!(matches!(invalid, Err(SchedulerError::InvalidConfiguration)));
+}
+
+#[test]
+fn test_next_occurrence_in_timezone() {
+    let after = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let schedule = CronSchedule::new().hour(9).unwrap().minute(0).unwrap();
+
+    // Kolkata is UTC+5:30 with no daylight saving
+    let local = schedule
+        .next_occurrence_in(after, &chrono_tz::Asia::Kolkata)
+        .unwrap();
+    assert_eq!(
+        local,
+        chrono_tz::Asia::Kolkata
+            .with_ymd_and_hms(2023, 1, 1, 14, 30, 0)
+            .unwrap()
+    );
+    assert_eq!(
+        local.with_timezone(&Utc),
+        schedule.next_occurrence(after).unwrap()
+    );
+}
+
+#[test]
+fn test_repeat_n_caps_cron() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let cron = CronSchedule::new().minute(15).unwrap();
+    let schedule = RepeatNSchedule::new(Box::new(cron), 3);
+
+    let first = schedule.next_occurrence(start).unwrap();
+    // Asking for the same occurrence again doesn't use up the cap, only firing it does
+    assert_eq!(schedule.next_occurrence(start), Some(first));
+    assert_eq!(schedule.remaining_occurrences(), Some(3));
+
+    let mut fires = Vec::new();
+    let mut after = start;
+    while let Some(next) = schedule.next_occurrence(after) {
+        schedule.record_fire(next);
+        fires.push(next);
+        after = next;
+    }
+    assert_eq!(fires.len(), 3);
+    assert_eq!(
+        fires[2],
+        Utc.with_ymd_and_hms(2023, 1, 1, 2, 15, 0).unwrap()
+    );
+    assert_eq!(
+        schedule.next_occurrence(start + chrono::TimeDelta::days(1)),
+        None
+    );
+
+    // Looking ahead doesn't use up the cap, and shows no more than it has left
+    let hour = |h: i64| start + chrono::TimeDelta::hours(h);
+    let schedule = RepeatNSchedule::new(Box::new(CronSchedule::new().minute(0).unwrap()), 3);
+    assert!(schedule.matches(hour(50), Duration::ZERO));
+    assert!(schedule.fires_within(hour(100), Duration::from_secs(7200)));
+    assert_eq!(schedule.preview(hour(5), 5).len(), 3);
+    let upcoming = Occurrences::new(&schedule, start).collect::<Vec<_>>();
+    assert_eq!(upcoming, vec![hour(0), hour(1), hour(2)]);
+    assert_eq!(schedule.occurrences_between(start, hour(100)), upcoming);
+
+    // Fires count wherever they land
+    schedule.record_fire(hour(10));
+    assert_eq!(schedule.preview(hour(10), 5), {
+        let gap = Duration::from_secs(3600);
+        vec![(hour(11), gap), (hour(12), gap)]
+    });
+    schedule.record_fire(hour(11));
+    schedule.record_fire(hour(12));
+    assert_eq!(schedule.next_occurrence(hour(12)), None);
+    assert!(schedule.is_finished(hour(12)));
+    assert_eq!(schedule.previous_occurrence(hour(50)), Some(hour(12)));
+    assert!(!schedule.matches(hour(50), Duration::ZERO));
+}
+
+#[test]
+fn test_repeat_n_caps_random_schedule() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+
+    // Each poll draws afresh, and only the draws that come due fire
+    for seed in 0..20 {
+        let random = RandomIntervalSchedule::new(Duration::from_secs(1), Duration::from_secs(3))
+            .unwrap()
+            .with_seed(seed);
+        let mut job = Job::recurring(RepeatNSchedule::new(Box::new(random), 3))
+            .task(())
+            .build()
+            .unwrap();
+        let fires = (0..200)
+            .map(|second| start + chrono::TimeDelta::seconds(second))
+            .filter(|time| job.should_execute(*time).is_some())
+            .count();
+        assert_eq!(fires, 3);
+        assert!(job.is_finished(start + chrono::TimeDelta::seconds(200)));
+    }
+}
+
+#[test]
+fn test_job_look_aheads_leave_repeat_count_alone() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let hour = |h: i64| start + chrono::TimeDelta::hours(h);
+    let hourly = RepeatNSchedule::new(Box::new(CronSchedule::new().minute(0).unwrap()), 3);
+    let mut job = Job::recurring(hourly).task(()).build().unwrap();
+
+    // Before the first poll, a plan counts from where it starts looking
+    let plan = job.to_plan(hour(24), Duration::from_secs(86400));
+    assert_eq!(plan.fire_times, vec![hour(24), hour(25), hour(26)]);
+    assert_eq!(job.count_between(hour(48), hour(100)), 3);
+    assert_eq!(job.occurrence_of_repeat(hour(30), 2), Some(hour(32)));
+    assert_eq!(job.deadline_for_next(hour(40)), Some(hour(41)));
+    assert!(job.alignment_error(hour(50)).is_some());
+    assert_eq!(
+        job.metrics_snapshot(hour(60)).seconds_until_next,
+        Some(3600)
+    );
+
+    // None of which moves the fires the first poll lines up
+    let fired: Vec<_> = (0..48)
+        .map(hour)
+        .filter(|time| job.should_execute(*time).is_some())
+        .collect();
+    assert_eq!(fired, vec![hour(0), hour(1), hour(2)]);
+}
+
+#[test]
+fn test_cron_to_icalendar_rrule() {
+    let daily = CronSchedule::new().hour(9).unwrap().minute(30).unwrap();
+    assert_eq!(
+        daily.to_icalendar_rrule().as_deref(),
+        Some("RRULE:FREQ=DAILY;BYHOUR=9;BYMINUTE=30")
+    );
+
+    let weekly = CronSchedule::new()
+        .weekday(0)
+        .unwrap()
+        .hour(8)
+        .unwrap()
+        .minute(0)
+        .unwrap();
+    assert_eq!(
+        weekly.to_icalendar_rrule().as_deref(),
+        Some("RRULE:FREQ=WEEKLY;BYDAY=MO;BYHOUR=8;BYMINUTE=0")
+    );
+
+    let monthly = CronSchedule::new()
+        .day(1)
+        .unwrap()
+        .hour(0)
+        .unwrap()
+        .minute(0)
+        .unwrap();
+    assert_eq!(
+        monthly.to_icalendar_rrule().as_deref(),
+        Some("RRULE:FREQ=MONTHLY;BYMONTHDAY=1;BYHOUR=0;BYMINUTE=0")
+    );
+
+    // Every minute of a Monday, and stepped days, have no single RRULE
+    assert_eq!(
+        CronSchedule::new().weekday(0).unwrap().to_icalendar_rrule(),
+        None
+    );
+    let stepped = CronSchedule::new().day_step(3).unwrap().hour(0).unwrap();
+    assert_eq!(stepped.minute(0).unwrap().to_icalendar_rrule(), None);
+}
+
+#[test]
+fn test_cron_second_jitter_stays_in_minute() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 1, 0).unwrap();
+    let schedule = CronSchedule::new()
+        .minute(0)
+        .unwrap()
+        .with_second_jitter(59)
+        .unwrap()
+        .with_jitter_seed(7);
+
+    let mut seconds = Vec::new();
+    let mut after = start;
+    for hour in 1..=10 {
+        let next = schedule.next_occurrence(after).unwrap();
+        assert_eq!((next.hour(), next.minute()), (hour, 0));
+        assert!(next.second() <= 59);
+        // Polling from inside the minute before the fire returns the same instant
+        assert_eq!(
+            schedule.next_occurrence(next - chrono::TimeDelta::seconds(1)),
+            Some(next)
+        );
+        seconds.push(next.second());
+        after = next;
+    }
+    assert!(seconds.iter().any(|second| *second != seconds[0]));
+
+    assert!(matches!(
+        CronSchedule::new().with_second_jitter(60),
+        Err(SchedulerError::InvalidConfiguration)
+    ));
+
+    // Seconds and jitter together must still land inside the matched minute
+    let at_second = |second: u32| {
+        CronSchedule::new()
+            .minute(0)
+            .unwrap()
+            .second(second)
+            .unwrap()
+    };
+    assert!(matches!(
+        at_second(30).with_second_jitter(45),
+        Err(SchedulerError::InvalidConfiguration)
+    ));
+    assert!(matches!(
+        CronSchedule::new()
+            .with_second_jitter(45)
+            .unwrap()
+            .second(30),
+        Err(SchedulerError::InvalidConfiguration)
+    ));
+    let schedule = at_second(14).with_second_jitter(45).unwrap();
+    let next = schedule.next_occurrence(start).unwrap();
+    assert_eq!((next.hour(), next.minute()), (1, 0));
+    assert!(next.second() >= 14);
+}
+
+#[test]
+fn test_nth_business_day_of_year() {
+    let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    let schedule = NthBusinessDaySchedule::new(10, nine).unwrap();
+
+    // 1 January 2023 is a Sunday, so business day 1 is Monday 2 January
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let tenth = schedule.next_occurrence(start).unwrap();
+    assert_eq!(tenth, Utc.with_ymd_and_hms(2023, 1, 13, 9, 0, 0).unwrap());
+    let twentieth = schedule.next_occurrence(tenth).unwrap();
+    assert_eq!(
+        twentieth,
+        Utc.with_ymd_and_hms(2023, 1, 27, 9, 0, 0).unwrap()
+    );
+
+    // The count restarts with the new year: 2024 begins on a Monday
+    let late = Utc.with_ymd_and_hms(2023, 12, 31, 0, 0, 0).unwrap();
+    assert_eq!(
+        schedule.next_occurrence(late),
+        Some(Utc.with_ymd_and_hms(2024, 1, 12, 9, 0, 0).unwrap())
+    );
+
+    assert_eq!(
+        NthBusinessDaySchedule::new(2, nine).unwrap().describe(),
+        "every 2nd business day of the year at 09:00"
+    );
+
+    assert!(matches!(
+        NthBusinessDaySchedule::new(0, nine),
+        Err(SchedulerError::InvalidRepetition)
+    ));
+}
+
+#[test]
+fn test_job_deadline_for_next() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let job = Job::builder()
+        .schedule(Box::new(
+            IntervalSchedule::new(Duration::from_secs(3600), start).unwrap(),
+        ))
+        .task("heartbeat")
+        .grace_period(Duration::from_secs(300))
+        .max_repeats(1)
+        .build()
+        .unwrap();
+
+    let now = start + chrono::TimeDelta::minutes(10);
+    assert_eq!(
+        job.deadline_for_next(now),
+        Some(Utc.with_ymd_and_hms(2023, 1, 1, 1, 5, 0).unwrap())
+    );
+
+    // A job past its end time has nothing to watch for
+    let ended = Job::builder()
+        .schedule(Box::new(
+            IntervalSchedule::new(Duration::from_secs(3600), start).unwrap(),
+        ))
+        .task("heartbeat")
+        .end_time(start + chrono::TimeDelta::minutes(30))
+        .build()
+        .unwrap();
+    assert_eq!(ended.deadline_for_next(now), None);
+}
+
+#[test]
+fn test_one_time_then_every() {
+    let kickoff = Utc::now().with_nanosecond(0).unwrap() + chrono::TimeDelta::days(1);
+    let schedule = OneTimeSchedule::new(kickoff)
+        .unwrap()
+        .then_every(Duration::from_secs(3600))
+        .unwrap();
+
+    let fires = schedule.occurrences_limited(Utc::now(), 3, kickoff + chrono::TimeDelta::days(1));
+    assert_eq!(
+        fires,
+        vec![
+            kickoff,
+            kickoff + chrono::TimeDelta::hours(1),
+            kickoff + chrono::TimeDelta::hours(2)
+        ]
+    );
+
+    assert!(matches!(
+        OneTimeSchedule::immediate().then_every(Duration::from_secs(3600)),
+        Err(SchedulerError::InvalidConfiguration)
+    ));
+}
+
+#[test]
+fn test_cron_from_fields() {
+    let schedule = CronSchedule::from_fields(Some(30), Some(9), Some(15), Some(6), None).unwrap();
+    assert_eq!(schedule.describe(), "cron 30 9 15 6 *");
+
+    let out_of_range = [
+        CronSchedule::from_fields(Some(60), None, None, None, None),
+        CronSchedule::from_fields(None, Some(24), None, None, None),
+        CronSchedule::from_fields(None, None, Some(0), None, None),
+        CronSchedule::from_fields(None, None, Some(32), None, None),
+        CronSchedule::from_fields(None, None, None, Some(13), None),
+        CronSchedule::from_fields(None, None, None, None, Some(7)),
+    ];
+    for result in out_of_range {
+        assert!(matches!(result, Err(SchedulerError::InvalidConfiguration)));
+    }
+}
+
+#[test]
+fn test_business_hours_max_occurrences_across_days() {
+    // Monday 9:00, with 16 half-hour slots in a 9:00 to 17:00 day
+    let start = Utc.with_ymd_and_hms(2023, 1, 2, 9, 0, 0).unwrap();
+    let schedule = BusinessHoursIntervalSchedule::new(
+        Duration::from_secs(1800),
+        start,
+        NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+    )
+    .unwrap()
+    .with_max_occurrences(20);
+
+    let fires = schedule.occurrences_limited(
+        start - chrono::TimeDelta::seconds(1),
+        usize::MAX,
+        start + chrono::TimeDelta::days(7),
+    );
+    assert_eq!(fires.len(), 20);
+    assert_eq!(
+        fires[16],
+        Utc.with_ymd_and_hms(2023, 1, 2, 17, 0, 0).unwrap()
+    );
+    // The count carries over into Tuesday's business hours
+    assert_eq!(
+        fires[17],
+        Utc.with_ymd_and_hms(2023, 1, 3, 9, 30, 0).unwrap()
+    );
+    assert_eq!(
+        fires[19],
+        Utc.with_ymd_and_hms(2023, 1, 3, 10, 30, 0).unwrap()
+    );
+    assert_eq!(schedule.next_occurrence(fires[19]), None);
+}
+
+#[test]
+fn test_weekday_cron_round_trip() {
+    let weekdays = [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ];
+    for (number, weekday) in weekdays.into_iter().enumerate() {
+        assert_eq!(weekday_to_cron(weekday), number as u32);
+        assert_eq!(cron_to_weekday(weekday_to_cron(weekday)).unwrap(), weekday);
+    }
+
+    assert!(matches!(
+        cron_to_weekday(7),
+        Err(SchedulerError::InvalidConfiguration)
+    ));
+}
+
+#[test]
+fn test_infer_schedule() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 2, 0, 15, 0).unwrap();
+
+    let hourly: Vec<_> = (0..5)
+        .map(|hour| start + chrono::TimeDelta::hours(hour))
+        .collect();
+    let inferred = infer_schedule(&hourly).unwrap();
+    assert_eq!(inferred.describe(), format!("every 1h from {start}"));
+    assert_eq!(
+        inferred.next_occurrence(hourly[4]),
+        Some(hourly[4] + chrono::TimeDelta::hours(1))
+    );
+
+    // Weekday mornings skip the weekend but keep the same time of day
+    let nine = Utc.with_ymd_and_hms(2023, 1, 2, 9, 0, 0).unwrap();
+    let mornings: Vec<_> = [0, 1, 2, 3, 4, 7, 8]
+        .into_iter()
+        .map(|day| nine + chrono::TimeDelta::days(day))
+        .collect();
+    assert_eq!(
+        infer_schedule(&mornings).unwrap().describe(),
+        "cron 0 9 * * 1-5"
+    );
+    let daily: Vec<_> = (0..4)
+        .map(|day| nine + chrono::TimeDelta::days(day))
+        .collect();
+    assert_eq!(infer_schedule(&daily).unwrap().describe(), "cron 0 9 * * *");
+    let weekly: Vec<_> = (0..3)
+        .map(|week| nine + chrono::TimeDelta::weeks(week))
+        .collect();
+    assert_eq!(
+        infer_schedule(&weekly).unwrap().describe(),
+        "cron 0 9 * * 1"
+    );
+
+    // Same time of day with other gaps is a plain interval, or nothing when irregular
+    let every_other_day: Vec<_> = (0..4)
+        .map(|day| nine + chrono::TimeDelta::days(2 * day))
+        .collect();
+    assert_eq!(
+        infer_schedule(&every_other_day).unwrap().describe(),
+        format!("every 2d from {nine}")
+    );
+    let uneven: Vec<_> = [0, 1, 5, 6]
+        .into_iter()
+        .map(|day| nine + chrono::TimeDelta::days(day))
+        .collect();
+    assert!(infer_schedule(&uneven).is_none());
+
+    let irregular = [
+        start,
+        start + chrono::TimeDelta::minutes(5),
+        start + chrono::TimeDelta::hours(3),
+    ];
+    assert!(infer_schedule(&irregular).is_none());
+}
+
+#[test]
+fn test_clamp_daily_moves_occurrences_into_window() {
+    // Sunsets looked up from a table, the first one before the window opens
+    let sunsets = vec![
+        Utc.with_ymd_and_hms(2023, 1, 15, 17, 30, 0).unwrap(),
+        Utc.with_ymd_and_hms(2023, 1, 16, 19, 45, 0).unwrap(),
+        Utc.with_ymd_and_hms(2023, 1, 17, 21, 30, 0).unwrap(),
+    ];
+    let events = sunsets.clone();
+    let sunset = CustomEventSchedule::new(Box::new(move |after| {
+        events.iter().copied().find(|event| *event > after)
+    }));
+    let six_pm = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+    let nine_pm = NaiveTime::from_hms_opt(21, 0, 0).unwrap();
+    let schedule = sunset.clamp_daily(six_pm, nine_pm).unwrap();
+
+    let start = Utc.with_ymd_and_hms(2023, 1, 15, 0, 0, 0).unwrap();
+    let fires = schedule.occurrences_limited(start, 10, start + chrono::TimeDelta::days(7));
+    assert_eq!(
+        fires,
+        vec![
+            Utc.with_ymd_and_hms(2023, 1, 15, 18, 0, 0).unwrap(),
+            sunsets[1],
+            Utc.with_ymd_and_hms(2023, 1, 17, 21, 0, 0).unwrap(),
+        ]
+    );
+
+    // A late occurrence pulled back before `after` is skipped
+    let late = Utc.with_ymd_and_hms(2023, 1, 17, 21, 15, 0).unwrap();
+    assert_eq!(schedule.next_occurrence(late), None);
+
+    let backwards = CronSchedule::new().clamp_daily(nine_pm, six_pm);
+    assert!(matches!(
+        backwards,
+        Err(SchedulerError::InvalidConfiguration)
+    ));
+}
+
+#[test]
+fn test_capped_horizon_hides_far_occurrences() {
+    let yearly = CronSchedule::from_fields(Some(0), Some(0), Some(1), Some(6), None).unwrap();
+    let schedule = yearly.capped_horizon(Duration::from_secs(30 * 86400));
+
+    let january = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(schedule.next_occurrence(january), None);
+
+    // The horizon runs from the first query, so asking later doesn't move it
+    let mid_may = Utc.with_ymd_and_hms(2023, 5, 15, 0, 0, 0).unwrap();
+    assert_eq!(schedule.next_occurrence(mid_may), None);
+    assert!(schedule.is_finished(mid_may));
+
+    let yearly = CronSchedule::from_fields(Some(0), Some(0), Some(1), Some(6), None).unwrap();
+    let schedule = yearly.capped_horizon(Duration::from_secs(30 * 86400));
+    assert_eq!(
+        schedule.next_occurrence(mid_may),
+        Some(Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap())
+    );
+
+    // Stepping through occurrences stops at the horizon rather than sliding it along
+    let daily = IntervalSchedule::new(Duration::from_secs(86400), january)
+        .unwrap()
+        .capped_horizon(Duration::from_secs(3 * 86400));
+    let fires = daily.occurrences_limited(january, 10, january + chrono::TimeDelta::days(30));
+    assert_eq!(fires.len(), 3);
+    assert_eq!(fires[2], january + chrono::TimeDelta::days(3));
+
+    let mut job = Job::recurring(
+        IntervalSchedule::new(Duration::from_secs(86400), january)
+            .unwrap()
+            .capped_horizon(Duration::from_secs(3 * 86400)),
+    )
+    .task(())
+    .build()
+    .unwrap();
+    let fired = (0..30)
+        .map(|day| january + chrono::TimeDelta::days(day))
+        .filter(|now| job.should_execute(*now).is_some())
+        .count();
+    assert_eq!(fired, 3);
+}
+
+#[test]
+fn test_job_pause_until() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let mut job = Job::builder()
+        .schedule(Box::new(
+            IntervalSchedule::new(Duration::from_secs(3600), start).unwrap(),
+        ))
+        .task("alert")
+        .build()
+        .unwrap();
+
+    let resume_at = start + chrono::TimeDelta::minutes(150);
+    job.pause_until(resume_at);
+    assert_eq!(
+        job.should_execute(start + chrono::TimeDelta::hours(1)),
+        None
+    );
+    assert_eq!(
+        job.should_execute(start + chrono::TimeDelta::hours(2)),
+        None
+    );
+
+    // Fires missed during the pause are skipped, and the next one runs as normal
+    assert_eq!(job.should_execute(resume_at), None);
+    assert_eq!(
+        job.deadline_for_next(start),
+        Some(start + chrono::TimeDelta::hours(3))
+    );
+    assert_eq!(
+        job.should_execute(start + chrono::TimeDelta::hours(3)),
+        Some(&"alert")
+    );
+}
+
+#[test]
+fn test_map_occurrences_to_next_hour() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 5, 0).unwrap();
+    let schedule = IntervalSchedule::new(Duration::from_secs(1200), start)
+        .unwrap()
+        .map_occurrences(Box::new(|time| {
+            time.with_minute(0).unwrap().with_second(0).unwrap() + chrono::TimeDelta::hours(1)
+        }));
+
+    let fires = schedule.occurrences_limited(
+        start - chrono::TimeDelta::minutes(5),
+        3,
+        start + chrono::TimeDelta::days(1),
+    );
+    // Several inner occurrences collapse onto the same hour without repeating it
+    assert_eq!(
+        fires,
+        vec![
+            Utc.with_ymd_and_hms(2023, 1, 1, 1, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2023, 1, 1, 2, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2023, 1, 1, 3, 0, 0).unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn test_preview_shows_gap_at_handoff() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let handoff = start + chrono::TimeDelta::hours(2);
+    let hourly = IntervalSchedule::new(Duration::from_secs(3600), start)
+        .unwrap()
+        .with_end_time(handoff);
+    let minutely = IntervalSchedule::new(Duration::from_secs(60), handoff).unwrap();
+    let schedule = CombinedSchedule::new(vec![Box::new(hourly), Box::new(minutely)]);
+
+    let preview = schedule.preview(start, 4);
+    let gaps: Vec<Duration> = preview.iter().map(|(_, gap)| *gap).collect();
+    assert_eq!(preview[1].0, handoff);
+    assert_eq!(
+        gaps,
+        vec![
+            Duration::from_secs(3600),
+            Duration::from_secs(3600),
+            Duration::from_secs(60),
+            Duration::from_secs(60)
+        ]
+    );
+}
+
+#[test]
+fn test_job_builder_accepts_unboxed_schedule() {
+    let cron = CronSchedule::from_fields(Some(0), Some(9), None, None, None).unwrap();
+    let mut job = Job::builder()
+        .schedule(cron)
+        .task("report")
+        .build()
+        .unwrap();
+
+    let nine = Utc.with_ymd_and_hms(2023, 1, 2, 9, 0, 0).unwrap();
+    assert_eq!(job.should_execute(nine), Some(&"report"));
+
+    // Already boxed schedules, concrete or dyn, still work
+    let interval = IntervalSchedule::new(Duration::from_secs(60), nine).unwrap();
+    assert!(Job::builder()
+        .schedule(Box::new(interval))
+        .task(())
+        .build()
+        .is_ok());
+    let boxed: Box<dyn Schedule> = Box::new(OneTimeSchedule::immediate());
+    assert!(Job::builder().schedule(boxed).task(()).build().is_ok());
+}
+
+#[test]
+fn test_instrumented_counts_collapsed_fires() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let minute = |m: i64| start + chrono::TimeDelta::minutes(m);
+
+    // Every minute, throttled to one fire per 5 minute slot
+    let every_minute = IntervalSchedule::new(Duration::from_secs(60), start)
+        .unwrap()
+        .instrumented();
+    let counter = every_minute.counter();
+    let throttled = every_minute.quantize(Duration::from_secs(300)).unwrap();
+    let mut job = Job::recurring(throttled).task(()).build().unwrap();
+
+    // Previews don't count
+    assert_eq!(
+        job.to_plan(start, Duration::from_secs(3600))
+            .fire_times
+            .len(),
+        12
+    );
+    assert_eq!(counter.counts(), SuppressionCounts::default());
+
+    let fired: Vec<_> = (0..20)
+        .map(minute)
+        .filter(|time| job.should_execute(*time).is_some())
+        .collect();
+    assert_eq!(fired, vec![minute(0), minute(5), minute(10), minute(15)]);
+    // Four occurrences collapsed into each slot before the latest fire
+    let counts = counter.counts();
+    assert_eq!(
+        counts,
+        SuppressionCounts {
+            emitted: 16,
+            suppressed: 12,
+        }
+    );
+    assert_eq!(counts.emitted - counts.suppressed, fired.len() as u64);
+
+    // Blackouts count what they drop too, and polling past a cap adds nothing
+    let every_minute = IntervalSchedule::new(Duration::from_secs(60), start)
+        .unwrap()
+        .instrumented();
+    let counter = every_minute.counter();
+    let blacked_out =
+        ExceptSchedule::new(Box::new(every_minute), vec![(minute(1), minute(3))]).unwrap();
+    let mut job = Job::recurring(RepeatNSchedule::new(Box::new(blacked_out), 4))
+        .task(())
+        .build()
+        .unwrap();
+    let fired: Vec<_> = (0..60)
+        .map(minute)
+        .filter(|time| job.should_execute(*time).is_some())
+        .collect();
+    assert_eq!(fired, vec![minute(0), minute(3), minute(4), minute(5)]);
+    // 00:01 and 00:02 in the blackout
+    assert_eq!(
+        counter.counts(),
+        SuppressionCounts {
+            emitted: 6,
+            suppressed: 2,
+        }
+    );
+}
+
+#[test]
+fn test_recording_schedule_captures_combined_sequence() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let combined = CombinedSchedule::new(vec![
+        Box::new(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap()),
+        Box::new(CronSchedule::from_fields(Some(30), None, None, None, None).unwrap()),
+    ]);
+    let schedule = RecordingSchedule::new(Box::new(combined));
+    let recording = schedule.recording();
+
+    let mut job = Job::builder().schedule(schedule).task(()).build().unwrap();
+    let mut now = start;
+    while now <= start + chrono::TimeDelta::hours(2) {
+        job.should_execute(now);
+        now += chrono::TimeDelta::minutes(10);
+    }
+
+    let minutes = |minutes: i64| start + chrono::TimeDelta::minutes(minutes);
+    assert_eq!(
+        *recording.borrow(),
+        vec![
+            minutes(0),
+            minutes(30),
+            minutes(60),
+            minutes(90),
+            minutes(120)
+        ]
+    );
+}
+
+#[test]
+fn test_interval_start_boundary() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let hour = chrono::TimeDelta::hours(1);
+    let before = start - chrono::TimeDelta::seconds(1);
+
+    // By default the start is occurrence 0, reached from any time before it
+    let included = IntervalSchedule::new(Duration::from_secs(3600), start).unwrap();
+    assert_eq!(included.next_occurrence(before), Some(start));
+    assert_eq!(included.next_occurrence(start), Some(start + hour));
+    assert!(included.matches(start, Duration::ZERO));
+
+    let excluded = IntervalSchedule::new(Duration::from_secs(3600), start)
+        .unwrap()
+        .with_start(IntervalStart::ExcludeStart);
+    assert_eq!(excluded.next_occurrence(before), Some(start + hour));
+    assert_eq!(excluded.next_occurrence(start), Some(start + hour));
+    assert!(!excluded.matches(start, Duration::ZERO));
+    assert!(excluded.matches(start + hour, Duration::ZERO));
+}
+
+#[test]
+fn test_schedule_span() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+
+    // Hourly until 5:30 fires last at 5:00
+    let bounded = IntervalSchedule::new(Duration::from_secs(3600), start)
+        .unwrap()
+        .with_end_time(start + chrono::TimeDelta::minutes(330));
+    assert_eq!(bounded.span(), Some(Duration::from_secs(5 * 3600)));
+    let unbounded = IntervalSchedule::new(Duration::from_secs(3600), start).unwrap();
+    assert_eq!(unbounded.span(), None);
+    // Fractions of a second count, so 1.5s steps until 6s fire last at 6s
+    let fractional = IntervalSchedule::new(Duration::from_millis(1500), start)
+        .unwrap()
+        .with_end_time(start + chrono::TimeDelta::seconds(6));
+    assert_eq!(fractional.span(), Some(Duration::from_secs(6)));
+
+    let explicit = ExplicitSchedule::new(vec![
+        start + chrono::TimeDelta::days(3),
+        start,
+        start + chrono::TimeDelta::days(1),
+    ])
+    .unwrap();
+    assert_eq!(explicit.span(), Some(Duration::from_secs(3 * 86400)));
+    assert_eq!(
+        explicit.next_occurrence(start),
+        Some(start + chrono::TimeDelta::days(1))
+    );
+    assert_eq!(
+        explicit.next_occurrence(start + chrono::TimeDelta::days(3)),
+        None
+    );
+
+    assert_eq!(OneTimeSchedule::immediate().span(), Some(Duration::ZERO));
+}
+
+#[test]
+fn test_recurring_limits_whichever_first() {
+    let start_time = Utc.with_ymd_and_hms(2023, 3, 1, 0, 0, 0).unwrap();
+    let interval = Duration::from_secs(3600);
+    let hourly = || IntervalSchedule::new(interval, start_time).unwrap();
+
+    // 10 times hourly until 3rd of March: the count binds first
+    let mut job = Job::recurring(hourly())
+        .limit_count(10)
+        .limit_until(Utc.with_ymd_and_hms(2023, 3, 3, 0, 0, 0).unwrap())
+        .task("reminder")
+        .build()
+        .unwrap();
+    let fired = (0..24)
+        .filter(|&i| job.should_execute(start_time + interval * i).is_some())
+        .count();
+    assert_eq!(fired, 10);
+
+    // Ending five hours in: the date binds first
+    let mut job = Job::recurring(hourly())
+        .limit_count(10)
+        .limit_until(start_time + interval * 5)
+        .task("reminder")
+        .build()
+        .unwrap();
+    let fired = (0..24)
+        .filter(|&i| job.should_execute(start_time + interval * i).is_some())
+        .count();
+    assert_eq!(fired, 5);
+}
+
+#[test]
+fn test_job_occurrence_of_repeat() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let job = Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap())
+        .limit_count(5)
+        .task(())
+        .build()
+        .unwrap();
+
+    assert_eq!(job.occurrence_of_repeat(start, 0), Some(start));
+    assert_eq!(
+        job.occurrence_of_repeat(start, 3),
+        Some(start + chrono::TimeDelta::hours(3))
+    );
+    // Only five runs are allowed
+    assert_eq!(job.occurrence_of_repeat(start, 5), None);
+}
+
+#[test]
+fn test_failure_policy_halt_vs_continue() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let hourly = || IntervalSchedule::new(Duration::from_secs(3600), start).unwrap();
+    let hours = |hour: i64| start + chrono::TimeDelta::hours(hour);
+
+    // Fails on the second run only
+    let flaky = || {
+        let mut runs = 0;
+        move || {
+            runs += 1;
+            if runs == 2 {
+                Err("database unavailable")
+            } else {
+                Ok(())
+            }
+        }
+    };
+
+    let mut halting = Job::recurring(hourly())
+        .task(flaky())
+        .failure_policy(FailurePolicy::HaltSchedule)
+        .build()
+        .unwrap();
+    assert_eq!(halting.run_if_due(hours(0)), Some(Ok(())));
+    assert_eq!(
+        halting.run_if_due(hours(1)),
+        Some(Err("database unavailable"))
+    );
+    assert_eq!(halting.run_if_due(hours(2)), None);
+    assert_eq!(halting.failures(), 1);
+    assert!(halting.metrics_snapshot(hours(2)).finished);
+
+    let mut continuing = Job::recurring(hourly()).task(flaky()).build().unwrap();
+    assert_eq!(continuing.run_if_due(hours(0)), Some(Ok(())));
+    assert_eq!(
+        continuing.run_if_due(hours(1)),
+        Some(Err("database unavailable"))
+    );
+    assert_eq!(continuing.run_if_due(hours(2)), Some(Ok(())));
+    assert_eq!(continuing.failures(), 1);
+}
+
+#[test]
+fn test_approaching_schedule_tightens_near_target() {
+    let target = Utc.with_ymd_and_hms(2023, 1, 1, 12, 0, 0).unwrap();
+    let schedule = ApproachingSchedule::new(
+        target,
+        Duration::from_secs(3600),
+        Duration::from_secs(600),
+        Duration::from_secs(3600),
+    )
+    .unwrap();
+    let before = |minutes: i64| target - chrono::TimeDelta::minutes(minutes);
+
+    // Hourly while far away, landing on the start of the near window
+    assert_eq!(schedule.next_occurrence(before(180)), Some(before(120)));
+    assert_eq!(schedule.next_occurrence(before(70)), Some(before(60)));
+    // Every ten minutes inside the window, ending at the target
+    assert_eq!(schedule.next_occurrence(before(60)), Some(before(50)));
+    assert_eq!(schedule.next_occurrence(before(5)), Some(target));
+    assert_eq!(schedule.next_occurrence(target), None);
+
+    // Counting back more steps than fit in a u32 still lands on the grid
+    let every_second = ApproachingSchedule::new(
+        target,
+        Duration::from_secs(1),
+        Duration::from_secs(1),
+        Duration::from_secs(60),
+    )
+    .unwrap();
+    let long_ago = Utc.with_ymd_and_hms(1823, 1, 1, 12, 0, 0).unwrap();
+    assert_eq!(
+        every_second.next_occurrence(long_ago),
+        Some(long_ago + chrono::TimeDelta::seconds(1))
+    );
+
+    assert!(matches!(
+        ApproachingSchedule::new(
+            target,
+            Duration::from_secs(60),
+            Duration::from_secs(600),
+            Duration::from_secs(3600)
+        ),
+        Err(SchedulerError::InvalidConfiguration)
+    ));
+}
+
+#[test]
+fn test_cron_local_timezone() {
+    let after = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let nine = CronSchedule::from_fields(Some(0), Some(9), None, None, None).unwrap();
+
+    // With a controlled +05:30 offset, 09:00 local is 03:30 UTC
+    let offset = chrono::FixedOffset::east_opt(5 * 3600 + 1800).unwrap();
+    assert_eq!(
+        nine.next_occurrence_in_zone(after, &offset),
+        Some(Utc.with_ymd_and_hms(2023, 1, 1, 3, 30, 0).unwrap())
+    );
+
+    // Whatever the machine's zone, the fire lands on 09:00 there
+    let local = nine.with_local_timezone();
+    let next = local.next_occurrence(after).unwrap();
+    let wall_clock = next.with_timezone(&chrono::Local);
+    assert_eq!((wall_clock.hour(), wall_clock.minute()), (9, 0));
+    assert!(next > after && next <= after + chrono::TimeDelta::days(2));
+}
+
+#[test]
+fn test_job_snooze_next() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let at = |hour: i64, minute: i64| {
+        start + chrono::TimeDelta::hours(hour) + chrono::TimeDelta::minutes(minute)
+    };
+    let mut job = Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap())
+        .task("standup reminder")
+        .build()
+        .unwrap();
+
+    assert!(job.should_execute(at(0, 0)).is_some());
+    assert_eq!(
+        job.snooze_next(at(0, 30), Duration::from_secs(1200)),
+        Some(at(1, 20))
+    );
+    assert_eq!(
+        job.to_plan(at(0, 30), Duration::from_secs(7200)).fire_times,
+        vec![at(1, 20), at(2, 0)]
+    );
+
+    // The 1:00 fire moves to 1:20, then 2:00 is back on the hour
+    assert!(job.should_execute(at(1, 0)).is_none());
+    assert!(job.should_execute(at(1, 10)).is_none());
+    assert!(job.should_execute(at(1, 20)).is_some());
+    assert!(job.should_execute(at(1, 30)).is_none());
+    assert!(job.should_execute(at(2, 0)).is_some());
+    assert_eq!(job.repeats, 3);
+}
+
+#[test]
+fn test_layered_schedule_override_window() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let at = |hour: i64, minute: i64| {
+        start + chrono::TimeDelta::hours(hour) + chrono::TimeDelta::minutes(minute)
+    };
+    let hourly = IntervalSchedule::new(Duration::from_secs(3600), start).unwrap();
+    let incident = IntervalSchedule::new(Duration::from_secs(300), at(10, 0)).unwrap();
+    let schedule = LayeredSchedule::new(Box::new(hourly))
+        .layer(at(10, 0), at(11, 0), Box::new(incident))
+        .unwrap();
+
+    assert_eq!(schedule.next_occurrence(at(8, 30)), Some(at(9, 0)));
+    assert_eq!(schedule.next_occurrence(at(9, 0)), Some(at(10, 0)));
+    // Every five minutes inside the window
+    assert_eq!(schedule.next_occurrence(at(10, 0)), Some(at(10, 5)));
+    assert_eq!(schedule.next_occurrence(at(10, 52)), Some(at(10, 55)));
+    // Back to hourly once the window closes
+    assert_eq!(schedule.next_occurrence(at(10, 57)), Some(at(11, 0)));
+    assert_eq!(schedule.next_occurrence(at(11, 0)), Some(at(12, 0)));
+
+    let fires = schedule.occurrences_limited(at(9, 30), 100, at(12, 0));
+    assert_eq!(fires.len(), 12 + 2);
+}
+
+#[test]
+fn test_job_to_crontab_line() {
+    let monday_nine = CronSchedule::from_fields(Some(0), Some(9), None, None, Some(0)).unwrap();
+    let job = Job::recurring(monday_nine).task(()).build().unwrap();
+    assert_eq!(
+        job.to_crontab_line("/usr/local/bin/backup --full")
+            .as_deref(),
+        Some("0 9 * * 1 /usr/local/bin/backup --full")
+    );
+
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let interval = Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap())
+        .task(())
+        .build()
+        .unwrap();
+    assert_eq!(interval.to_crontab_line("backup"), None);
+
+    let limited = Job::recurring(CronSchedule::new().minute(0).unwrap())
+        .limit_count(3)
+        .task(())
+        .build()
+        .unwrap();
+    assert_eq!(limited.to_crontab_line("backup"), None);
+
+    // A day field can't hold both listed days and a step
+    let stepped_days = CronSchedule::new()
+        .minute(0)
+        .unwrap()
+        .days(1..=10)
+        .unwrap()
+        .day_step(3)
+        .unwrap();
+    assert_eq!(stepped_days.to_cron_expression(), None);
+    let stepped = Job::recurring(stepped_days).task(()).build().unwrap();
+    assert_eq!(stepped.to_crontab_line("backup"), None);
+}
+
+#[test]
+fn test_fires_within_leap_day() {
+    let leap_day = CronSchedule::from_fields(Some(0), Some(0), Some(29), Some(2), None).unwrap();
+    let year = Duration::from_secs(365 * 86400);
+
+    let non_leap = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    assert!(!leap_day.fires_within(non_leap, Duration::from_secs(300 * 86400)));
+
+    let leap = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    assert!(leap_day.fires_within(leap, year));
+    assert!(!leap_day.fires_within(leap, Duration::from_secs(30 * 86400)));
+}
+
+#[test]
+fn test_cron_last_minus_days() {
+    let schedule = CronSchedule::new()
+        .day_spec(DaySpec::LastMinus(2))
+        .unwrap()
+        .hour(23)
+        .unwrap()
+        .minute(0)
+        .unwrap();
+    assert_eq!(schedule.describe(), "cron 0 23 L-2 * *");
+
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let days: Vec<(u32, u32)> = schedule
+        .occurrences_limited(start, 6, start + chrono::TimeDelta::days(365))
+        .iter()
+        .map(|time| (time.month(), time.day()))
+        .collect();
+    assert_eq!(
+        days,
+        vec![(1, 29), (1, 30), (1, 31), (2, 26), (2, 27), (2, 28)]
+    );
+
+    // A leap February shifts the window to the 27th to 29th
+    let leap = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        schedule.next_occurrence(leap),
+        Some(Utc.with_ymd_and_hms(2024, 2, 27, 23, 0, 0).unwrap())
+    );
+
+    assert!(matches!(
+        CronSchedule::new().day_spec(DaySpec::LastMinus(28)),
+        Err(SchedulerError::InvalidConfiguration)
+    ));
+    let never = CronSchedule::new()
+        .day_step(8)
+        .unwrap()
+        .day_spec(DaySpec::LastMinus(0))
+        .unwrap();
+    assert!(!never.is_satisfiable());
+}
+
+#[test]
+fn test_random_interval_replay() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let production =
+        RandomIntervalSchedule::new(Duration::from_secs(60), Duration::from_secs(600)).unwrap();
+
+    // Record a few fires, then replay them exactly
+    let mut observed = Vec::new();
+    let mut after = start;
+    for _ in 0..4 {
+        let next = production.next_occurrence(after).unwrap();
+        observed.push(next);
+        after = next;
+    }
+
+    let replay = RandomIntervalSchedule::replay(observed.clone()).unwrap();
+    let replayed = replay.occurrences_limited(start, 10, start + chrono::TimeDelta::days(1));
+    assert_eq!(replayed, observed);
+}
+
+#[test]
+fn test_job_builder_require_fires() {
+    let now = Utc::now();
+    let daily_from_tomorrow = || {
+        IntervalSchedule::new(Duration::from_secs(86400), now + chrono::TimeDelta::days(1)).unwrap()
+    };
+
+    // Ends before the first fire tomorrow
+    let result = Job::recurring(daily_from_tomorrow())
+        .end_time(now + chrono::TimeDelta::hours(12))
+        .require_fires(true)
+        .task(())
+        .build();
+    assert!(matches!(result, Err(SchedulerError::Unsatisfiable)));
+
+    // Opt-in only, and a wider window passes
+    assert!(Job::recurring(daily_from_tomorrow())
+        .end_time(now + chrono::TimeDelta::hours(12))
+        .task(())
+        .build()
+        .is_ok());
+    assert!(Job::recurring(daily_from_tomorrow())
+        .end_time(now + chrono::TimeDelta::days(3))
+        .require_fires(true)
+        .task(())
+        .build()
+        .is_ok());
+}
+
+#[test]
+fn test_job_run_with_context() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let seen = std::rc::Rc::new(RefCell::new(Vec::new()));
+    let recorder = std::rc::Rc::clone(&seen);
+    let mut job = Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap())
+        .task(move |context: OccurrenceContext| recorder.borrow_mut().push(context))
+        .build()
+        .unwrap();
+
+    // Polled slightly late, the context still names the scheduled instant
+    let late = chrono::TimeDelta::milliseconds(500);
+    job.run_with_context(start + late);
+    assert!(job
+        .run_with_context(start + chrono::TimeDelta::minutes(30))
+        .is_none());
+    job.run_with_context(start + chrono::TimeDelta::hours(1) + late);
+
+    let expected = vec![
+        OccurrenceContext {
+            scheduled_time: start,
+            repeat_index: 0,
+            catch_up: false,
+        },
+        OccurrenceContext {
+            scheduled_time: start + chrono::TimeDelta::hours(1),
+            repeat_index: 1,
+            catch_up: false,
+        },
+    ];
+    assert_eq!(*seen.borrow(), expected);
+}
+
+#[test]
+fn test_job_alignment_error() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let job = Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap())
+        .task(|| {})
+        .build()
+        .unwrap();
+    let at = |minutes: i64| start + chrono::TimeDelta::minutes(minutes);
+
+    assert_eq!(job.alignment_error(at(120)), Some(Duration::ZERO));
+    // Off either way, measured to whichever occurrence is nearer
+    assert_eq!(
+        job.alignment_error(at(132)),
+        Some(Duration::from_secs(12 * 60))
+    );
+    assert_eq!(
+        job.alignment_error(at(168)),
+        Some(Duration::from_secs(12 * 60))
+    );
+    // Before the first occurrence only the next one counts
+    assert_eq!(
+        job.alignment_error(start - chrono::TimeDelta::minutes(5)),
+        Some(Duration::from_secs(5 * 60))
+    );
+}
+
+#[test]
+fn test_scheduler_runs_due_jobs() {
+    // Jobs are registered against the current time
+    let start = Utc::now().with_nanosecond(0).unwrap();
+    let runs = std::rc::Rc::new(Cell::new(0));
+    let counter = std::rc::Rc::clone(&runs);
+
+    let mut scheduler = Scheduler::new();
+    let hourly = scheduler.add_job(
+        Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap())
+            .limit_count(2)
+            .task(move || counter.set(counter.get() + 1))
+            .build()
+            .unwrap(),
+    );
+    let half_past = scheduler.add_job(
+        Job::recurring(
+            IntervalSchedule::new(
+                Duration::from_secs(3600),
+                start + chrono::TimeDelta::minutes(30),
+            )
+            .unwrap(),
+        )
+        .task(|| {})
+        .build()
+        .unwrap(),
+    );
+
+    assert_eq!(scheduler.run_pending(start), vec![hourly]);
+    assert_eq!(
+        scheduler.next_wake(),
+        Some(start + chrono::TimeDelta::minutes(30))
+    );
+    assert_eq!(
+        scheduler.run_pending(start + chrono::TimeDelta::minutes(30)),
+        vec![half_past]
+    );
+    // A late wake-up still runs the 1:00 fire once
+    assert_eq!(
+        scheduler.run_pending(start + chrono::TimeDelta::minutes(70)),
+        vec![hourly]
+    );
+    assert_eq!(runs.get(), 2);
+
+    // The hourly job is done after two runs
+    assert!(scheduler.remove_job(half_past));
+    assert_eq!(scheduler.next_wake(), None);
+}
+
+#[test]
+fn test_scheduler_runs_immediate_job_once() {
+    let runs = std::rc::Rc::new(Cell::new(0));
+    let counter = std::rc::Rc::clone(&runs);
+    let mut scheduler = Scheduler::new();
+    let id = scheduler.add_job(
+        Job::builder()
+            .schedule(OneTimeSchedule::immediate())
+            .task(move || counter.set(counter.get() + 1))
+            .build()
+            .unwrap(),
+    );
+
+    // Registering lines the immediate fire up without running it
+    let now = Utc::now();
+    assert!(scheduler.next_wake().is_some_and(|wake| wake <= now));
+    assert_eq!(scheduler.run_pending(now), vec![id]);
+    assert!(scheduler.run_pending(now).is_empty());
+    assert_eq!(scheduler.next_wake(), None);
+    assert_eq!(runs.get(), 1);
+}
+
+#[test]
+fn test_scheduler_keeps_polling_idle_jobs() {
+    let start = Utc::now().with_nanosecond(0).unwrap();
+    let enabled = std::rc::Rc::new(Cell::new(false));
+    let flag = std::rc::Rc::clone(&enabled);
+
+    let mut scheduler = Scheduler::new();
+    let gated = scheduler.add_job(
+        Job::recurring(
+            IntervalSchedule::new(Duration::from_secs(60), start)
+                .unwrap()
+                .gated_by(Box::new(move || flag.get())),
+        )
+        .task(|| {})
+        .build()
+        .unwrap(),
+    );
+
+    // Added while switched off, so nothing is lined up, but the job isn't finished either
+    assert!(scheduler.run_pending(start).is_empty());
+    assert_eq!(scheduler.next_wake(), None);
+    assert!(!scheduler.is_finished(start));
+
+    // Switching on is picked up on the next pass
+    enabled.set(true);
+    let minute = start + chrono::TimeDelta::minutes(1);
+    assert_eq!(scheduler.run_pending(minute), vec![gated]);
+    assert_eq!(
+        scheduler.next_wake(),
+        Some(start + chrono::TimeDelta::minutes(2))
+    );
+
+    // A far off fire hidden by a horizon cap isn't the end of the job either
+    let mut scheduler = Scheduler::new();
+    scheduler.add_job(
+        Job::recurring(
+            IntervalSchedule::new(
+                Duration::from_secs(86400),
+                start + chrono::TimeDelta::hours(1),
+            )
+            .unwrap()
+            .capped_horizon(Duration::from_secs(60)),
+        )
+        .task(|| {})
+        .build()
+        .unwrap(),
+    );
+    assert_eq!(scheduler.next_wake(), None);
+    assert!(!scheduler.is_finished(start));
+
+    // Whereas a job out of repeats is
+    let mut scheduler = Scheduler::new();
+    scheduler.add_job(
+        Job::recurring(IntervalSchedule::new(Duration::from_secs(60), start).unwrap())
+            .limit_count(1)
+            .task(|| {})
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(scheduler.run_pending(start).len(), 1);
+    assert!(scheduler.is_finished(start));
+}
+
+#[test]
+fn test_scheduler_batches_due_jobs() {
+    let start = Utc::now().with_nanosecond(0).unwrap();
+    let at = |minutes: i64| start + chrono::TimeDelta::minutes(minutes);
+    let batches = std::rc::Rc::new(RefCell::new(Vec::new()));
+    let recorder = std::rc::Rc::clone(&batches);
+
+    let mut scheduler = Scheduler::new()
+        .with_batching(Duration::from_secs(5 * 60), move |ids, opened| {
+            recorder.borrow_mut().push((ids, opened))
+        })
+        .unwrap();
+    let ids: Vec<JobId> = [0, 1, 2, 10]
+        .into_iter()
+        .map(|minutes| {
+            scheduler.add_job(
+                Job::recurring(
+                    IntervalSchedule::new(Duration::from_secs(3600), at(minutes)).unwrap(),
+                )
+                .task(|| panic!("batched jobs go through on_batch"))
+                .build()
+                .unwrap(),
+            )
+        })
+        .collect();
+
+    // The first three fall inside the window opened by the first
+    assert!(scheduler.run_pending(start).is_empty());
+    assert_eq!(scheduler.next_wake(), Some(at(5)));
+    assert_eq!(scheduler.run_pending(at(5)), ids[..3]);
+    assert_eq!(*batches.borrow(), vec![(ids[..3].to_vec(), start)]);
+
+    // The last one opens a batch of its own
+    assert!(scheduler.run_pending(at(10)).is_empty());
+    assert_eq!(scheduler.run_pending(at(15)), ids[3..]);
+    assert_eq!(batches.borrow().len(), 2);
+}
+
+#[test]
+fn test_scheduler_replaces_job_with_same_dedup_key() {
+    let start = Utc::now().with_nanosecond(0).unwrap();
+    let ran = std::rc::Rc::new(RefCell::new(Vec::new()));
+    let report = |version: &'static str| {
+        let recorder = std::rc::Rc::clone(&ran);
+        Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap())
+            .dedup_key("nightly-report")
+            .task(move || recorder.borrow_mut().push(version))
+            .build()
+            .unwrap()
+    };
+
+    let mut scheduler = Scheduler::new();
+    let first = scheduler.add_job(report("v1"));
+    let other = scheduler.add_job(
+        Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap())
+            .task(|| {})
+            .build()
+            .unwrap(),
+    );
+
+    // Registering the key again, eg: after a config reload, swaps in the new definition
+    let second = scheduler.add_job(report("v2"));
+    assert_eq!(second, first);
+    assert_eq!(scheduler.job_ids(), vec![first, other]);
+    scheduler.run_pending(start);
+    assert_eq!(*ran.borrow(), vec!["v2"]);
+}
+
+#[test]
+fn test_scheduler_upcoming_within() {
+    let now = Utc::now();
+    let at = |minutes: i64| now + chrono::TimeDelta::minutes(minutes);
+
+    let mut scheduler = Scheduler::new();
+    let mut ids = Vec::new();
+    // Added out of order, to check the result is sorted by fire time
+    for minutes in [10, 30, 2] {
+        ids.push(
+            scheduler.add_job(
+                Job::builder()
+                    .schedule(OneTimeSchedule::new(at(minutes)).unwrap())
+                    .task(|| {})
+                    .build()
+                    .unwrap(),
+            ),
+        );
+    }
+
+    assert_eq!(
+        scheduler.upcoming_within(now, Duration::from_secs(15 * 60)),
+        vec![(ids[2], at(2)), (ids[0], at(10))]
+    );
+}
+
+#[test]
+fn test_scheduler_aggregate_fire_count() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let hour = start..start + chrono::TimeDelta::hours(1);
+
+    let mut scheduler = Scheduler::new();
+    scheduler.add_job(
+        Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap())
+            .task(|| {})
+            .build()
+            .unwrap(),
+    );
+    scheduler.add_job(
+        Job::recurring(IntervalSchedule::new(Duration::from_secs(60), start).unwrap())
+            .task(|| {})
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(scheduler.aggregate_fire_count(hour.clone()), 61);
+
+    // Limits cap what a job adds
+    scheduler.add_job(
+        Job::recurring(IntervalSchedule::new(Duration::from_secs(60), start).unwrap())
+            .limit_count(10)
+            .task(|| {})
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(scheduler.aggregate_fire_count(hour), 71);
+}
+
+#[test]
+fn test_scheduler_backfill() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let at = |minutes: i64| start + chrono::TimeDelta::minutes(minutes);
+    let ran = std::rc::Rc::new(RefCell::new(Vec::new()));
+
+    // Each task records the historical instant it is handed
+    let mut scheduler = Scheduler::new();
+    let recorder = std::rc::Rc::clone(&ran);
+    scheduler.add_job_with_context(
+        Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap())
+            .task(move |context: OccurrenceContext| {
+                recorder
+                    .borrow_mut()
+                    .push(("hourly", context.scheduled_time))
+            })
+            .build()
+            .unwrap(),
+    );
+    let recorder = std::rc::Rc::clone(&ran);
+    scheduler.add_job_with_context(
+        Job::recurring(IntervalSchedule::new(Duration::from_secs(5400), at(30)).unwrap())
+            .task(move |context: OccurrenceContext| {
+                recorder
+                    .borrow_mut()
+                    .push(("ninety", context.scheduled_time))
+            })
+            .build()
+            .unwrap(),
+    );
+
+    let fires = scheduler.backfill(start, at(180));
+    assert_eq!(
+        *ran.borrow(),
+        vec![
+            ("hourly", at(0)),
+            ("ninety", at(30)),
+            ("hourly", at(60)),
+            ("hourly", at(120)),
+            ("ninety", at(120)),
+        ]
+    );
+    assert_eq!(fires.len(), 5);
+
+    // Plain tasks can be backfilled too, they just aren't told the instant
+    let runs = std::rc::Rc::new(Cell::new(0));
+    let counter = std::rc::Rc::clone(&runs);
+    let mut scheduler = Scheduler::new();
+    let id = scheduler.add_job(
+        Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap())
+            .task(move || counter.set(counter.get() + 1))
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(
+        scheduler.backfill(start, at(120)),
+        vec![(id, at(0)), (id, at(60))]
+    );
+    assert_eq!(runs.get(), 2);
+}
+
+#[test]
+fn test_scheduler_find_duplicates() {
+    let report = || {
+        Job::recurring(CronSchedule::daily())
+            .label("report")
+            .task(|| {})
+            .build()
+            .unwrap()
+    };
+
+    let mut scheduler = Scheduler::new();
+    let first = scheduler.add_job(report());
+    // Built differently, but firing at the same times
+    let second = scheduler.add_job(
+        Job::recurring(CronSchedule::new().hour(0).unwrap().minute(0).unwrap())
+            .label("report")
+            .task(|| {})
+            .build()
+            .unwrap(),
+    );
+    // Same schedule under another label, and the same label on another schedule
+    scheduler.add_job(
+        Job::recurring(CronSchedule::daily())
+            .label("backup")
+            .task(|| {})
+            .build()
+            .unwrap(),
+    );
+    scheduler.add_job(
+        Job::recurring(CronSchedule::hourly())
+            .label("report")
+            .task(|| {})
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(scheduler.find_duplicates(), vec![(first, second)]);
+
+    let third = scheduler.add_job(report());
+    assert_eq!(
+        scheduler.find_duplicates(),
+        vec![(first, second), (first, third), (second, third)]
+    );
+}
+
+#[test]
+fn test_scheduler_coalesces_slack_jobs() {
+    let start = Utc::now().with_nanosecond(0).unwrap();
+    let at = |minutes: i64| start + chrono::TimeDelta::minutes(minutes);
+    let slack = Duration::from_secs(600);
+
+    let mut scheduler = Scheduler::new();
+    let on_the_hour = scheduler.add_job(
+        Job::recurring(
+            IntervalSchedule::new(Duration::from_secs(3600), start)
+                .unwrap()
+                .with_slack(slack),
+        )
+        .task(|| {})
+        .build()
+        .unwrap(),
+    );
+    let five_past = scheduler.add_job(
+        Job::recurring(
+            IntervalSchedule::new(Duration::from_secs(3600), at(5))
+                .unwrap()
+                .with_slack(slack),
+        )
+        .task(|| {})
+        .build()
+        .unwrap(),
+    );
+
+    // Held back waiting for company, then both go out when the second comes due
+    assert!(scheduler.run_pending(start).is_empty());
+    assert_eq!(scheduler.next_wake(), Some(at(10)));
+    assert_eq!(scheduler.run_pending(at(5)), vec![on_the_hour, five_past]);
+
+    // On its own, a slack job fires once its slack runs out
+    assert!(scheduler.remove_job(five_past));
+    assert!(scheduler.run_pending(at(60)).is_empty());
+    assert_eq!(scheduler.run_pending(at(70)), vec![on_the_hour]);
+}
+
+#[test]
+fn test_scheduler_fires_overdue_job_on_start() {
+    let now = Utc::now();
+    let runs = std::rc::Rc::new(Cell::new(0));
+    let counter = std::rc::Rc::clone(&runs);
+
+    let mut scheduler = Scheduler::new().with_fire_overdue_on_start(true);
+    let stop = scheduler.stop_handle();
+    // Last fired two days ago, so at least yesterday's midnight fire went by while down
+    scheduler.add_job(
+        Job::recurring(CronSchedule::daily())
+            .last_fired(now - chrono::TimeDelta::days(2))
+            .task(move || {
+                counter.set(counter.get() + 1);
+                stop.stop();
+            })
+            .build()
+            .unwrap(),
+    );
+    // Never fired, so there's nothing to tell it missed one
+    scheduler.add_job(
+        Job::recurring(CronSchedule::daily())
+            .task(|| panic!("nothing to catch up on"))
+            .build()
+            .unwrap(),
+    );
+
+    // The catch-up fire stops the scheduler before the next midnight
+    scheduler.start();
+    assert_eq!(runs.get(), 1);
+}
+
+#[test]
+fn test_scheduler_passes_occurrence_context() {
+    let start = Utc::now().with_nanosecond(0).unwrap() + chrono::TimeDelta::minutes(1);
+    let seen = std::rc::Rc::new(RefCell::new(Vec::new()));
+    let recorder = std::rc::Rc::clone(&seen);
+
+    let mut scheduler = Scheduler::new();
+    let id = scheduler.add_job_with_context(
+        Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap())
+            .task(move |context| recorder.borrow_mut().push(context))
+            .build()
+            .unwrap(),
+    );
+
+    // Each run is told the instant it services, even when polled late
+    let late = chrono::TimeDelta::seconds(20);
+    assert_eq!(scheduler.run_pending(start + late), vec![id]);
+    let next = start + chrono::TimeDelta::hours(1);
+    assert_eq!(scheduler.run_pending(next + late), vec![id]);
+    assert_eq!(
+        *seen.borrow(),
+        vec![
+            OccurrenceContext {
+                scheduled_time: start,
+                repeat_index: 0,
+                catch_up: false,
+            },
+            OccurrenceContext {
+                scheduled_time: next,
+                repeat_index: 1,
+                catch_up: false,
+            },
+        ]
+    );
+
+    // A catch-up run on start is marked as one, for the fire it missed
+    let now = Utc::now();
+    let seen = std::rc::Rc::new(RefCell::new(Vec::new()));
+    let recorder = std::rc::Rc::clone(&seen);
+    let mut scheduler = Scheduler::new().with_fire_overdue_on_start(true);
+    let stop = scheduler.stop_handle();
+    scheduler.add_job_with_context(
+        Job::recurring(CronSchedule::daily())
+            .last_fired(now - chrono::TimeDelta::days(2))
+            .task(move |context| {
+                recorder.borrow_mut().push(context);
+                stop.stop();
+            })
+            .build()
+            .unwrap(),
+    );
+    scheduler.start();
+    let midnight = now.date_naive().and_time(NaiveTime::MIN).and_utc();
+    assert_eq!(
+        *seen.borrow(),
+        vec![OccurrenceContext {
+            scheduled_time: midnight,
+            repeat_index: 0,
+            catch_up: true,
+        }]
+    );
+}
+
+#[test]
+fn test_scheduler_start_and_stop() {
+    let runs = std::rc::Rc::new(Cell::new(0));
+    let counter = std::rc::Rc::clone(&runs);
+    let mut scheduler = Scheduler::new();
+    let stop = scheduler.stop_handle();
+    // Due as soon as it starts, and stops the scheduler from inside its task
+    scheduler.add_job(
+        Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), Utc::now()).unwrap())
+            .task(move || {
+                counter.set(counter.get() + 1);
+                stop.stop();
+            })
+            .build()
+            .unwrap(),
+    );
+    scheduler.start();
+    assert_eq!(runs.get(), 1);
+
+    // Stopping from another thread wakes a scheduler waiting an hour for its next fire
+    let mut scheduler = Scheduler::new();
+    scheduler.add_job(
+        Job::recurring(
+            IntervalSchedule::new(
+                Duration::from_secs(3600),
+                Utc::now() + chrono::TimeDelta::hours(1),
+            )
+            .unwrap(),
+        )
+        .task(|| panic!("stopped before the first fire"))
+        .build()
+        .unwrap(),
+    );
+    let stop = scheduler.stop_handle();
+    let stopper = std::thread::spawn(move || stop.stop());
+    scheduler.start();
+    stopper.join().unwrap();
+    assert!(scheduler.stop_handle().is_stopped());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn test_async_scheduler_runs_async_tasks() {
+    let runs = std::rc::Rc::new(Cell::new(0));
+    let counter = std::rc::Rc::clone(&runs);
+    let start = Utc::now();
+
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async move {
+            let mut scheduler = AsyncScheduler::new();
+            scheduler.spawn(
+                Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap())
+                    .limit_count(3)
+                    .task(move || {
+                        let counter = std::rc::Rc::clone(&counter);
+                        async move {
+                            tokio::task::yield_now().await;
+                            counter.set(counter.get() + 1);
+                        }
+                    })
+                    .build()
+                    .unwrap(),
+            );
+            scheduler.join().await;
+        })
+        .await;
+
+    // Paused time skips the hour long sleeps
+    assert_eq!(runs.get(), 3);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn test_async_scheduler_runs_immediate_job_once() {
+    let runs = std::rc::Rc::new(Cell::new(0));
+    let counter = std::rc::Rc::clone(&runs);
+
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async move {
+            let mut scheduler = AsyncScheduler::new();
+            // Lining the immediate fire up before waiting doesn't run it
+            scheduler.spawn(
+                Job::builder()
+                    .schedule(OneTimeSchedule::immediate())
+                    .task(move || {
+                        let counter = std::rc::Rc::clone(&counter);
+                        async move { counter.set(counter.get() + 1) }
+                    })
+                    .build()
+                    .unwrap(),
+            );
+            scheduler.join().await;
+        })
+        .await;
+
+    assert_eq!(runs.get(), 1);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn test_async_scheduler_passes_occurrence_context() {
+    let start = Utc::now().with_nanosecond(0).unwrap() + chrono::TimeDelta::seconds(2);
+    let seen = std::rc::Rc::new(RefCell::new(Vec::new()));
+    let recorder = std::rc::Rc::clone(&seen);
+
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async move {
+            let mut scheduler = AsyncScheduler::new();
+            scheduler.spawn_with_context(
+                Job::recurring(IntervalSchedule::new(Duration::from_secs(1), start).unwrap())
+                    .max_repeats(2)
+                    .task(move |context| {
+                        let recorder = std::rc::Rc::clone(&recorder);
+                        async move { recorder.borrow_mut().push(context) }
+                    })
+                    .build()
+                    .unwrap(),
+            );
+            scheduler.join().await;
+        })
+        .await;
+
+    let seen = seen.borrow();
+    let times: Vec<_> = seen.iter().map(|context| context.scheduled_time).collect();
+    let indices: Vec<_> = seen.iter().map(|context| context.repeat_index).collect();
+    assert_eq!(times, vec![start, start + chrono::TimeDelta::seconds(1)]);
+    assert_eq!(indices, vec![0, 1]);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn test_async_scheduler_limits_resource_group() {
+    let running = std::rc::Rc::new(Cell::new(0));
+    let most_running = std::rc::Rc::new(Cell::new(0));
+    let finished = std::rc::Rc::new(RefCell::new(Vec::new()));
+
+    let local = tokio::task::LocalSet::new();
+    let (running_, most_running_, finished_) = (
+        std::rc::Rc::clone(&running),
+        std::rc::Rc::clone(&most_running),
+        std::rc::Rc::clone(&finished),
+    );
+    local
+        .run_until(async move {
+            let mut scheduler = AsyncScheduler::new().with_group_limit("db", 1).unwrap();
+            // All three come due together and each holds the database for a minute
+            for _ in 0..3 {
+                let (running, most_running, finished) = (
+                    std::rc::Rc::clone(&running_),
+                    std::rc::Rc::clone(&most_running_),
+                    std::rc::Rc::clone(&finished_),
+                );
+                scheduler.spawn(
+                    Job::builder()
+                        .schedule(OneTimeSchedule::immediate())
+                        .resource_group("db")
+                        .task(move || {
+                            let (running, most_running, finished) = (
+                                std::rc::Rc::clone(&running),
+                                std::rc::Rc::clone(&most_running),
+                                std::rc::Rc::clone(&finished),
+                            );
+                            async move {
+                                running.set(running.get() + 1);
+                                most_running.set(most_running.get().max(running.get()));
+                                tokio::time::sleep(Duration::from_secs(60)).await;
+                                running.set(running.get() - 1);
+                                finished.borrow_mut().push(tokio::time::Instant::now());
+                            }
+                        })
+                        .build()
+                        .unwrap(),
+                );
+            }
+            scheduler.join().await;
+        })
+        .await;
+
+    // One after another, a minute apart
+    assert_eq!(most_running.get(), 1);
+    let finished = finished.borrow();
+    assert_eq!(finished.len(), 3);
+    assert!(finished
+        .windows(2)
+        .all(|pair| pair[1] - pair[0] >= Duration::from_secs(60)));
+    assert!(matches!(
+        AsyncScheduler::new().with_group_limit("db", 0),
+        Err(SchedulerError::InvalidConfiguration)
+    ));
+}
+
+#[test]
+fn test_thread_pool_runs_tasks_off_scheduler_thread() {
+    let pool = ThreadPoolExecutor::new(2).unwrap();
+    let runs = Arc::new(Mutex::new(Vec::new()));
+    let start = Utc::now().with_nanosecond(0).unwrap();
+    let (release, released) = mpsc::channel::<()>();
+    let released = Arc::new(Mutex::new(released));
+
+    let mut scheduler = Scheduler::new();
+    for _ in 0..2 {
+        let (runs, released) = (Arc::clone(&runs), Arc::clone(&released));
+        // Held until the scheduler thread is back from run_pending, which it couldn't be if
+        // the task ran inline. The timeout turns that into a failure rather than a hang
+        let slow = move || {
+            let waited = released
+                .lock()
+                .unwrap()
+                .recv_timeout(Duration::from_secs(5))
+                .is_ok();
+            runs.lock().unwrap().push((thread::current().id(), waited));
+        };
+        let job = Job::recurring(IntervalSchedule::new(Duration::from_secs(3600), start).unwrap())
+            .task(slow)
+            .build()
+            .unwrap();
+        scheduler.add_pooled_job(job, &pool);
+    }
+
+    // Both slow tasks are handed off rather than run inline
+    assert_eq!(scheduler.run_pending(start).len(), 2);
+    release.send(()).unwrap();
+    release.send(()).unwrap();
+
+    drop(pool);
+    let runs = runs.lock().unwrap();
+    assert_eq!(runs.len(), 2);
+    assert!(runs
+        .iter()
+        .all(|(id, waited)| *id != thread::current().id() && *waited));
+
+    assert!(matches!(
+        ThreadPoolExecutor::new(0),
+        Err(SchedulerError::InvalidConfiguration)
+    ));
+}
+
+#[test]
+fn test_thread_pool_survives_panicking_tasks() {
+    let pool = ThreadPoolExecutor::new(1).unwrap();
+    let runs = Arc::new(Mutex::new(0));
+    let start = Utc::now().with_nanosecond(0).unwrap();
+
+    let mut scheduler = Scheduler::new();
+    let counter = Arc::clone(&runs);
+    let flaky = move || {
+        let run = {
+            let mut runs = counter.lock().unwrap();
+            *runs += 1;
+            *runs
+        };
+        if run == 1 {
+            panic!("first run fails");
+        }
+    };
+    let job = Job::recurring(IntervalSchedule::new(Duration::from_secs(60), start).unwrap())
+        .task(flaky)
+        .build()
+        .unwrap();
+    scheduler.add_pooled_job(job, &pool);
+
+    // The only worker outlives the panic, and so does the task for its next run
+    scheduler.run_pending(start);
+    scheduler.run_pending(start + chrono::TimeDelta::minutes(1));
+    let executed = Arc::new(Mutex::new(false));
+    let flag = Arc::clone(&executed);
+    pool.execute(move || *flag.lock().unwrap() = true);
+
+    drop(pool);
+    assert_eq!(*runs.lock().unwrap(), 2);
+    assert!(*executed.lock().unwrap());
+}
+
+#[test]
+fn test_cron_from_str() {
+    let noon_monday: CronSchedule = "0 12 * * MON".parse().unwrap();
+    assert_eq!(noon_monday.describe(), "cron 0 12 * * 1");
+    let monday = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+    assert_eq!(
+        noon_monday.next_occurrence(monday),
+        Some(Utc.with_ymd_and_hms(2023, 1, 2, 12, 0, 0).unwrap())
+    );
+
+    // Sunday is 0 or 7, and steps and last days work in the day field
+    let sunday: CronSchedule = "30 9 */3 jan 7".parse().unwrap();
+    assert_eq!(sunday.describe(), "cron 30 9 */3 1 0");
+    let last: CronSchedule = "0 0 L-2 * *".parse().unwrap();
+    assert_eq!(last.describe(), "cron 0 0 L-2 * *");
+
+    let field_of = |expression: &str| match expression.parse::<CronSchedule>() {
+        Err(SchedulerError::InvalidCronField { field, .. }) => field,
+        other => panic!(
+            "expected a field error, got {:?}",
+            other.map(|s| s.describe())
+        ),
+    };
+    assert_eq!(field_of("60 * * * *"), "minute");
+    assert_eq!(field_of("0 24 * * *"), "hour");
+    assert_eq!(field_of("0 0 32 * *"), "day-of-month");
+    assert_eq!(field_of("0 0 * FOO *"), "month");
+    assert_eq!(field_of("0 0 * * 8"), "day-of-week");
+    assert_eq!(field_of("0 0 * *"), "expression");
+
+    let message = "0 0 * * FUNDAY"
+        .parse::<CronSchedule>()
+        .err()
+        .unwrap()
+        .to_string();
+    assert_eq!(message, "Invalid cron day-of-week field: \"FUNDAY\"");
+}
+
+#[test]
+fn test_cron_value_sets() {
+    let schedule = CronSchedule::new()
+        .minute(0)
+        .unwrap()
+        .hours([18, 9, 12])
+        .unwrap()
+        .days(1..=5)
+        .unwrap();
+    assert_eq!(schedule.describe(), "cron 0 9,12,18 1-5 * *");
+
+    let start = Utc.with_ymd_and_hms(2023, 1, 5, 13, 0, 0).unwrap();
+    assert_eq!(
+        schedule.occurrences_limited(start, 3, start + Duration::from_secs(86400 * 30)),
+        vec![
+            Utc.with_ymd_and_hms(2023, 1, 5, 18, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2023, 2, 1, 9, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2023, 2, 1, 12, 0, 0).unwrap(),
+        ]
+    );
+    assert!(schedule.matches(
+        Utc.with_ymd_and_hms(2023, 3, 2, 12, 0, 0).unwrap(),
+        Duration::ZERO
+    ));
+    assert!(!schedule.matches(
+        Utc.with_ymd_and_hms(2023, 3, 6, 12, 0, 0).unwrap(),
+        Duration::ZERO
+    ));
+
+    // Parsed lists and ranges, with weekdays that wrap past Sunday
+    let weekend: CronSchedule = "0,30 8 * * SAT,SUN".parse().unwrap();
+    assert_eq!(weekend.describe(), "cron 0,30 8 * * 0,6");
+    let saturday = Utc.with_ymd_and_hms(2023, 1, 7, 8, 15, 0).unwrap();
+    assert_eq!(
+        weekend.next_occurrence(saturday),
+        Some(Utc.with_ymd_and_hms(2023, 1, 7, 8, 30, 0).unwrap())
+    );
+    let weekdays: CronSchedule = "0 9 * * MON-FRI".parse().unwrap();
+    assert_eq!(weekdays.to_cron_expression().unwrap(), "0 9 * * 1-5");
+
+    assert!(CronSchedule::new().hours([]).is_err());
+    assert!(CronSchedule::new().days(0..3).is_err());
+    assert!("0 9 * * FRI-MON".parse::<CronSchedule>().is_err());
+}
+
+#[test]
+fn test_cron_field_steps() {
+    let describe = |expression: &str| expression.parse::<CronSchedule>().unwrap().describe();
+    assert_eq!(describe("*/15 * * * *"), "cron 0,15,30,45 * * * *");
+    assert_eq!(
+        describe("0 */2 * * *"),
+        "cron 0 0,2,4,6,8,10,12,14,16,18,20,22 * * *"
+    );
+    assert_eq!(describe("0-30/10 * * * *"), "cron 0,10,20,30 * * * *");
+    assert_eq!(
+        describe("0 0 1-15/2 * *"),
+        "cron 0 0 1,3,5,7,9,11,13,15 * *"
+    );
+    assert_eq!(describe("0 0 * JAN-DEC/3 *"), "cron 0 0 * 1,4,7,10 *");
+    assert_eq!(describe("45/5 * * * *"), "cron 45,50,55 * * * *");
+    assert_eq!(describe("0 0 * * MON-FRI/2"), "cron 0 0 * * 1,3,5");
+    assert_eq!(describe("*/20 0 0 * * ?"), "cron 0,20,40 0 0 * * *");
+
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 7, 0).unwrap();
+    let quarter_hours: CronSchedule = "*/15 * * * *".parse().unwrap();
+    assert_eq!(
+        quarter_hours.next_occurrence(start),
+        Some(Utc.with_ymd_and_hms(2023, 1, 1, 0, 15, 0).unwrap())
+    );
+
+    for invalid in [
+        "*/0 * * * *",
+        "*/x * * * *",
+        "30-10/5 * * * *",
+        "0 24/2 * * *",
+        "/5 * * * *",
+    ] {
+        assert!(invalid.parse::<CronSchedule>().is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn test_cron_day_or_weekday() {
+    // A listed day of the month and day of the week fire on either, as in Vixie cron
+    let schedule: CronSchedule = "0 12 1 * MON".parse().unwrap();
+    let start = Utc.with_ymd_and_hms(2024, 3, 1, 13, 0, 0).unwrap();
+    assert_eq!(
+        schedule.occurrences_limited(start, 3, start + Duration::from_secs(86400 * 40)),
+        vec![
+            Utc.with_ymd_and_hms(2024, 3, 4, 12, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 3, 11, 12, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 3, 18, 12, 0, 0).unwrap(),
+        ]
+    );
+    assert!(schedule.matches(
+        Utc.with_ymd_and_hms(2024, 2, 1, 12, 0, 0).unwrap(),
+        Duration::ZERO
+    ));
+    assert!(schedule.matches(
+        Utc.with_ymd_and_hms(2024, 2, 5, 12, 0, 0).unwrap(),
+        Duration::ZERO
+    ));
+    assert!(!schedule.matches(
+        Utc.with_ymd_and_hms(2024, 2, 6, 12, 0, 0).unwrap(),
+        Duration::ZERO
+    ));
+    assert_eq!(schedule.to_cron_expression().unwrap(), "0 12 1 * 1");
+    assert_eq!(schedule.to_icalendar_rrule(), None);
+
+    // Built from fields both must match, which a cron expression can't say
+    let both = CronSchedule::new()
+        .minute(0)
+        .unwrap()
+        .hour(12)
+        .unwrap()
+        .day(1)
+        .unwrap()
+        .weekday(0)
+        .unwrap();
+    assert_eq!(
+        both.next_occurrence(start),
+        Some(Utc.with_ymd_and_hms(2024, 4, 1, 12, 0, 0).unwrap())
+    );
+    assert_eq!(both.to_cron_expression(), None);
+}
+
+#[test]
+fn test_cron_seconds_field() {
+    let schedule = CronSchedule::new().second(30).unwrap();
+    assert_eq!(schedule.describe(), "cron 30 * * * * *");
+    assert_eq!(schedule.to_cron_expression(), None);
+
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 12, 0, 30).unwrap();
+    assert_eq!(
+        schedule.occurrences_limited(
+            start - Duration::from_secs(1),
+            3,
+            start + Duration::from_secs(600)
+        ),
+        vec![
+            start,
+            start + Duration::from_secs(60),
+            start + Duration::from_secs(120),
+        ]
+    );
+    assert!(schedule.matches(start, Duration::ZERO));
+    assert!(!schedule.matches(start - Duration::from_secs(30), Duration::ZERO));
+
+    // Several seconds within the same matched minute
+    let twice = CronSchedule::new()
+        .minute(5)
+        .unwrap()
+        .seconds([0, 15])
+        .unwrap();
+    assert_eq!(
+        twice.next_occurrence(Utc.with_ymd_and_hms(2023, 1, 1, 12, 5, 0).unwrap()),
+        Some(Utc.with_ymd_and_hms(2023, 1, 1, 12, 5, 15).unwrap())
+    );
+    assert_eq!(
+        twice.next_occurrence(Utc.with_ymd_and_hms(2023, 1, 1, 12, 5, 15).unwrap()),
+        Some(Utc.with_ymd_and_hms(2023, 1, 1, 13, 5, 0).unwrap())
+    );
+    assert!(CronSchedule::new().second(60).is_err());
+}
+
+#[test]
+fn test_cron_shorthands() {
+    assert_eq!(CronSchedule::hourly().describe(), "cron 0 * * * *");
+    assert_eq!(CronSchedule::daily().describe(), "cron 0 0 * * *");
+    assert_eq!(CronSchedule::weekly().describe(), "cron 0 0 * * 0");
+    assert_eq!(CronSchedule::monthly().describe(), "cron 0 0 1 * *");
+    assert_eq!(CronSchedule::yearly().describe(), "cron 0 0 1 1 *");
+
+    for (shorthand, expected) in [
+        ("@hourly", "0 * * * *"),
+        ("@daily", "0 0 * * *"),
+        ("@midnight", "0 0 * * *"),
+        ("@weekly", "0 0 * * 0"),
+        ("@monthly", "0 0 1 * *"),
+        ("@annually", "0 0 1 1 *"),
+    ] {
+        let schedule: CronSchedule = shorthand.parse().unwrap();
+        assert_eq!(schedule.to_cron_expression().unwrap(), expected);
+    }
+
+    let saturday = Utc.with_ymd_and_hms(2023, 1, 7, 12, 0, 0).unwrap();
+    assert_eq!(
+        CronSchedule::weekly().next_occurrence(saturday),
+        Some(Utc.with_ymd_and_hms(2023, 1, 8, 0, 0, 0).unwrap())
+    );
+    assert!(matches!(
+        "@fortnightly".parse::<CronSchedule>(),
+        Err(SchedulerError::InvalidCronField {
+            field: "expression",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_cron_quartz_extensions() {
+    let third_saturday: CronSchedule = "0 9 * * 6#3".parse().unwrap();
+    assert_eq!(third_saturday.describe(), "cron 0 9 * * 6#3");
+    assert_eq!(third_saturday.to_cron_expression(), None);
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        third_saturday.occurrences_limited(start, 2, start + Duration::from_secs(86400 * 60)),
+        vec![
+            Utc.with_ymd_and_hms(2023, 1, 21, 9, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2023, 2, 18, 9, 0, 0).unwrap(),
+        ]
+    );
+    assert!(third_saturday.matches(
+        Utc.with_ymd_and_hms(2023, 1, 21, 9, 0, 0).unwrap(),
+        Duration::ZERO
+    ));
+    assert!(!third_saturday.matches(
+        Utc.with_ymd_and_hms(2023, 1, 14, 9, 0, 0).unwrap(),
+        Duration::ZERO
+    ));
+
+    let next_after = |expression: &str, after: DateTime<Utc>| {
+        expression
+            .parse::<CronSchedule>()
+            .unwrap()
+            .next_occurrence(after)
+    };
+    let date = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap();
+
+    // Last Friday, nearest weekdays that stay within the month, and the last weekday
+    assert_eq!(next_after("0 0 * * 5L", start), Some(date(2023, 1, 27)));
+    assert_eq!(next_after("0 0 15W * ?", start), Some(date(2023, 1, 16)));
+    assert_eq!(next_after("0 0 1W 4 ?", start), Some(date(2023, 4, 3)));
+    assert_eq!(next_after("0 0 LW 4 ?", start), Some(date(2023, 4, 28)));
+
+    // Seven fields with seconds and a year, which runs out after the last listed year
+    let new_year: CronSchedule = "0 0 0 1 1 ? 2030".parse().unwrap();
+    assert_eq!(new_year.describe(), "cron 0 0 0 1 1 * 2030");
+    assert_eq!(new_year.next_occurrence(start), Some(date(2030, 1, 1)));
+    assert_eq!(new_year.next_occurrence(date(2030, 1, 1)), None);
+
+    // The 1st is never a third Saturday
+    assert_eq!(next_after("0 0 1 * 6#3", start), None);
+    assert!("0 0 * * 6#6".parse::<CronSchedule>().is_err());
+    assert!("0 0 0 * * * 1969".parse::<CronSchedule>().is_err());
+}
+
+#[test]
+fn test_cron_from_on_calendar() {
+    let describe = |expression: &str| {
+        CronSchedule::from_on_calendar(expression)
+            .unwrap()
+            .describe()
+    };
+    assert_eq!(describe("Mon..Fri *-*-* 10:00:00"), "cron 0 10 * * 1-5");
+    assert_eq!(describe("*-*-01 06:30"), "cron 30 6 1 * *");
+    assert_eq!(describe("*:0/15"), "cron 0,15,30,45 * * * *");
+    assert_eq!(describe("Sat,Sunday 12:00:30 UTC"), "cron 30 0 12 * * 0,6");
+    assert_eq!(describe("weekly"), "cron 0 0 * * 1");
+    assert_eq!(describe("quarterly"), "cron 0 0 1 1,4,7,10 *");
+    assert_eq!(describe("2030-01-01"), "cron 0 0 0 1 1 * 2030");
+
+    let schedule = CronSchedule::from_on_calendar("Mon..Fri *-*-* 10:00:00").unwrap();
+    let saturday = Utc.with_ymd_and_hms(2023, 1, 7, 12, 0, 0).unwrap();
+    assert_eq!(
+        schedule.next_occurrence(saturday),
+        Some(Utc.with_ymd_and_hms(2023, 1, 9, 10, 0, 0).unwrap())
+    );
+
+    let field_of = |expression: &str| match CronSchedule::from_on_calendar(expression) {
+        Err(SchedulerError::InvalidCronField { field, .. }) => field,
+        other => panic!(
+            "expected a field error, got {:?}",
+            other.map(|s| s.describe())
+        ),
+    };
+    assert_eq!(field_of("Mon..Fri *-13-* 10:00"), "month");
+    assert_eq!(field_of("Fri..Mon 10:00"), "weekday");
+    assert_eq!(field_of("*-*-* 25:00"), "hour");
+    assert_eq!(field_of("*-*-* 10:00 Europe/Berlin"), "expression");
+}
+
+#[cfg(feature = "chrono-tz")]
+#[test]
+fn test_named_timezone_schedules() {
+    let new_york = chrono_tz::America::New_York;
+    let nine = CronSchedule::new()
+        .minute(0)
+        .unwrap()
+        .hour(9)
+        .unwrap()
+        .with_timezone(new_york);
+    assert_eq!(nine.describe(), "cron 0 9 * * * in America/New_York");
+
+    // 9am local is 14:00 UTC in winter and 13:00 UTC in summer
+    let winter = Utc.with_ymd_and_hms(2023, 1, 10, 0, 0, 0).unwrap();
+    let summer = Utc.with_ymd_and_hms(2023, 7, 10, 0, 0, 0).unwrap();
+    assert_eq!(
+        nine.next_occurrence(winter),
+        Some(Utc.with_ymd_and_hms(2023, 1, 10, 14, 0, 0).unwrap())
+    );
+    assert_eq!(
+        nine.next_occurrence(summer),
+        Some(Utc.with_ymd_and_hms(2023, 7, 10, 13, 0, 0).unwrap())
+    );
+
+    // A daily interval keeps 9am local across the spring-forward change
+    let daily = IntervalSchedule::new(
+        Duration::from_secs(86400),
+        Utc.with_ymd_and_hms(2023, 3, 11, 14, 0, 0).unwrap(),
+    )
+    .unwrap()
+    .with_timezone(new_york);
+    let start = Utc.with_ymd_and_hms(2023, 3, 11, 14, 0, 0).unwrap();
+    assert_eq!(
+        daily.next_occurrence(start),
+        Some(Utc.with_ymd_and_hms(2023, 3, 12, 13, 0, 0).unwrap())
+    );
+    assert!(daily.matches(
+        Utc.with_ymd_and_hms(2023, 3, 13, 13, 0, 0).unwrap(),
+        Duration::ZERO
+    ));
+}
+
+#[cfg(feature = "chrono-tz")]
+#[test]
+fn test_cron_parses_trailing_timezone() {
+    let schedule: CronSchedule = "0 9 * * 1-5 America/New_York".parse().unwrap();
+    assert_eq!(schedule.describe(), "cron 0 9 * * 1-5 in America/New_York");
+
+    // 9am in New York is 14:00 UTC in winter, and Friday rolls over to Monday
+    let friday = Utc.with_ymd_and_hms(2023, 1, 6, 15, 0, 0).unwrap();
+    assert_eq!(
+        schedule.next_occurrence(friday),
+        Some(Utc.with_ymd_and_hms(2023, 1, 9, 14, 0, 0).unwrap())
+    );
+
+    // Quartz expressions take one too
+    let quartz: CronSchedule = "30 0 9 * * ? 2024 Europe/London".parse().unwrap();
+    assert_eq!(
+        quartz.next_occurrence(Utc.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap()),
+        Some(Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 30).unwrap())
+    );
+
+    assert!(matches!(
+        "0 9 * * 1-5 Mars/Olympus_Mons".parse::<CronSchedule>(),
+        Err(SchedulerError::InvalidCronField {
+            field: "timezone",
+            ..
+        })
+    ));
+}
+
+#[cfg(all(feature = "chrono-tz", feature = "serde"))]
+#[test]
+fn test_cron_timezone_serialization() {
+    let schedule = CronSchedule::new()
+        .hour(9)
+        .unwrap()
+        .minute(0)
+        .unwrap()
+        .with_timezone(chrono_tz::America::New_York);
+
+    let json = serde_json::to_string(&schedule).unwrap();
+    assert!(json.contains(r#""timezone":"America/New_York""#));
+
+    let restored: CronSchedule = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.describe(), schedule.describe());
+    let summer = Utc.with_ymd_and_hms(2023, 7, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        restored.next_occurrence(summer),
+        Some(Utc.with_ymd_and_hms(2023, 7, 1, 13, 0, 0).unwrap())
+    );
+    assert_eq!(
+        restored.occurrences_limited(summer, 10, DateTime::<Utc>::MAX_UTC),
+        schedule.occurrences_limited(summer, 10, DateTime::<Utc>::MAX_UTC)
+    );
+
+    let unknown = json.replace("America/New_York", "Mars/Olympus_Mons");
+    let error = serde_json::from_str::<CronSchedule>(&unknown)
+        .err()
+        .unwrap();
+    assert!(error.to_string().contains("Mars/Olympus_Mons"));
+}
+
+#[test]
+fn test_interval_local_timezone() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 12, 0, 0).unwrap();
+    let daily = IntervalSchedule::new(Duration::from_secs(86400), start)
+        .unwrap()
+        .with_local_timezone();
+    assert!(daily.describe().ends_with(" local time"));
+
+    // Whatever the machine's zone, every fire keeps the start's local time of day
+    let local_time = |time: DateTime<Utc>| time.with_timezone(&chrono::Local).time();
+    let fires = daily.occurrences_limited(start, 200, start + chrono::TimeDelta::days(400));
+    assert_eq!(fires.len(), 200);
+    assert!(fires
+        .iter()
+        .all(|&fire| local_time(fire) == local_time(start)));
+}
+
+#[test]
+fn test_cron_exports_skip_non_utc_clocks() {
+    let nine = || CronSchedule::new().minute(0).unwrap().hour(9).unwrap();
+    assert!(nine().to_cron_expression().is_some());
+    assert!(nine().to_icalendar_rrule().is_some());
+
+    // The exported fields would otherwise be read in UTC or the host's zone
+    let local = nine().with_local_timezone();
+    assert_eq!(local.to_cron_expression(), None);
+    assert_eq!(local.to_icalendar_rrule(), None);
+    #[cfg(feature = "chrono-tz")]
+    {
+        let new_york = nine().with_timezone(chrono_tz::America::New_York);
+        assert_eq!(new_york.to_cron_expression(), None);
+        assert_eq!(new_york.to_icalendar_rrule(), None);
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+#[test]
+fn test_dst_policies() {
+    let half_past = |hour| {
+        CronSchedule::new()
+            .minute(30)
+            .unwrap()
+            .hour(hour)
+            .unwrap()
+            .with_timezone(chrono_tz::America::New_York)
+    };
+    let utc = |m, d, h, min| Utc.with_ymd_and_hms(2023, m, d, h, min, 0).unwrap();
+
+    // 02:30 doesn't exist on 12 March: skipped by default, or run when the gap ends at 03:00
+    let spring = utc(3, 12, 5, 0);
+    assert_eq!(
+        half_past(2).next_occurrence(spring),
+        Some(utc(3, 13, 6, 30))
+    );
+    assert_eq!(
+        half_past(2)
+            .with_dst_policy(DstPolicy::NextValid)
+            .next_occurrence(spring),
+        Some(utc(3, 12, 7, 0))
+    );
+
+    // 01:30 happens twice on 5 November: once by default, or on both passes
+    let fall = utc(11, 5, 4, 0);
+    let first_pass = utc(11, 5, 5, 30);
+    assert_eq!(
+        half_past(1).occurrences_limited(fall, 2, utc(11, 7, 0, 0)),
+        vec![first_pass, utc(11, 6, 6, 30)]
+    );
+    assert_eq!(
+        half_past(1)
+            .with_dst_policy(DstPolicy::RunBoth)
+            .occurrences_limited(fall, 3, utc(11, 7, 0, 0)),
+        vec![first_pass, utc(11, 5, 6, 30), utc(11, 6, 6, 30)]
+    );
+
+    // Interval grids follow the same policy
+    let hourly = IntervalSchedule::new(Duration::from_secs(3600), utc(11, 5, 4, 30))
+        .unwrap()
+        .with_timezone(chrono_tz::America::New_York)
+        .with_dst_policy(DstPolicy::RunBoth);
+    assert_eq!(
+        hourly.occurrences_limited(utc(11, 5, 4, 30), 3, utc(11, 6, 0, 0)),
+        vec![first_pass, utc(11, 5, 6, 30), utc(11, 5, 7, 30)]
+    );
+}
+
+#[test]
+fn test_previous_occurrence() {
+    let at = |d, h, m| Utc.with_ymd_and_hms(2023, 1, d, h, m, 0).unwrap();
+
+    let nine = CronSchedule::new().minute(0).unwrap().hour(9).unwrap();
+    assert_eq!(nine.previous_occurrence(at(2, 8, 0)), Some(at(1, 9, 0)));
+    assert_eq!(nine.previous_occurrence(at(2, 9, 0)), Some(at(1, 9, 0)));
+    assert_eq!(nine.previous_occurrence(at(2, 9, 1)), Some(at(2, 9, 0)));
+
+    let grid = IntervalSchedule::new(Duration::from_secs(900), at(1, 0, 0))
+        .unwrap()
+        .with_end_time(at(1, 1, 0));
+    assert_eq!(grid.previous_occurrence(at(1, 0, 40)), Some(at(1, 0, 30)));
+    assert_eq!(grid.previous_occurrence(at(1, 0, 30)), Some(at(1, 0, 15)));
+    assert_eq!(grid.previous_occurrence(at(3, 0, 0)), Some(at(1, 1, 0)));
+    assert_eq!(grid.previous_occurrence(at(1, 0, 0)), None);
+
+    let explicit = ExplicitSchedule::new(vec![at(1, 12, 0), at(5, 12, 0)]).unwrap();
+    assert_eq!(
+        explicit.previous_occurrence(at(4, 0, 0)),
+        Some(at(1, 12, 0))
+    );
+    assert_eq!(explicit.previous_occurrence(at(1, 12, 0)), None);
+
+    // Composites look back through their children, so a missed run shows up after downtime
+    let combined = CombinedSchedule::new(vec![Box::new(nine), Box::new(explicit)]);
+    let last_seen = at(2, 0, 0);
+    let restarted = at(5, 13, 0);
+    let missed = combined.previous_occurrence(restarted);
+    assert_eq!(missed, Some(at(5, 12, 0)));
+    assert!(missed.is_some_and(|missed| missed > last_seen));
+}
+
+#[test]
+fn test_occurrences_between() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let hours = |h: u64| start + Duration::from_secs(3600 * h);
+    let hourly = IntervalSchedule::new(Duration::from_secs(3600), start).unwrap();
+
+    // The start is included and the end excluded
+    assert_eq!(
+        hourly.occurrences_between(hours(1), hours(4)),
+        vec![hours(1), hours(2), hours(3)]
+    );
+    assert!(hourly.occurrences_between(hours(4), hours(4)).is_empty());
+
+    let weekdays: CronSchedule = "0 9 * * MON-FRI".parse().unwrap();
+    let week = weekdays.occurrences_between(start, start + chrono::TimeDelta::days(7));
+    assert_eq!(week.len(), 5);
+    assert!(week.iter().all(|fire| fire.hour() == 9));
+}
+
+#[test]
+fn test_iter_from() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let hourly = IntervalSchedule::new(Duration::from_secs(3600), start).unwrap();
+    let upcoming: Vec<_> = hourly.iter_from(start).take(3).collect();
+    assert_eq!(
+        upcoming,
+        vec![
+            start,
+            start + Duration::from_secs(3600),
+            start + Duration::from_secs(7200),
+        ]
+    );
+
+    // Boxed schedules iterate too, and the iterator ends with the schedule
+    let boxed: Box<dyn Schedule> =
+        Box::new(ExplicitSchedule::new(vec![start, start + Duration::from_secs(60)]).unwrap());
+    assert_eq!(
+        Occurrences::new(&*boxed, start + Duration::from_secs(1)).count(),
+        1
+    );
+    assert_eq!(
+        Occurrences::new(&*boxed, start).last(),
+        Some(start + Duration::from_secs(60))
+    );
+}
+
+#[test]
+fn test_nth_weekday_schedule() {
+    let third_saturday = NthWeekdaySchedule::new(Weekday::Sat, 3)
+        .unwrap()
+        .with_time(NaiveTime::from_hms_opt(10, 0, 0).unwrap());
+    assert_eq!(third_saturday.describe(), "3rd Sat of every month at 10:00");
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        third_saturday.iter_from(start).take(2).collect::<Vec<_>>(),
+        vec![
+            Utc.with_ymd_and_hms(2023, 1, 21, 10, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2023, 2, 18, 10, 0, 0).unwrap(),
+        ]
+    );
+
+    // Negative indices count back from the end of the month
+    let last_friday = NthWeekdaySchedule::new(Weekday::Fri, -1).unwrap();
+    assert_eq!(
+        last_friday.iter_from(start).take(2).collect::<Vec<_>>(),
+        vec![
+            Utc.with_ymd_and_hms(2023, 1, 27, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2023, 2, 24, 0, 0, 0).unwrap(),
+        ]
+    );
+
+    // Months without a fifth Monday are skipped
+    let fifth_monday = NthWeekdaySchedule::new(Weekday::Mon, 5).unwrap();
+    assert_eq!(
+        fifth_monday.next_occurrence(start),
+        Some(Utc.with_ymd_and_hms(2023, 1, 30, 0, 0, 0).unwrap())
+    );
+    assert_eq!(
+        fifth_monday.next_occurrence(Utc.with_ymd_and_hms(2023, 1, 31, 0, 0, 0).unwrap()),
+        Some(Utc.with_ymd_and_hms(2023, 5, 29, 0, 0, 0).unwrap())
+    );
+
+    assert!(NthWeekdaySchedule::new(Weekday::Mon, 0).is_err());
+    assert!(NthWeekdaySchedule::new(Weekday::Mon, -6).is_err());
+}
+
+#[test]
+fn test_cron_month_end() {
+    let month_end = CronSchedule::month_end().hour(23).unwrap();
+    assert_eq!(month_end.describe(), "cron 0 23 L * *");
+
+    // 31, 29 (leap year), 31 and 30 day months
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let ends: Vec<u32> = month_end
+        .iter_from(start)
+        .take(4)
+        .map(|end| end.day())
+        .collect();
+    assert_eq!(ends, vec![31, 29, 31, 30]);
+    assert_eq!(
+        month_end.next_occurrence(Utc.with_ymd_and_hms(2023, 2, 1, 0, 0, 0).unwrap()),
+        Some(Utc.with_ymd_and_hms(2023, 2, 28, 23, 0, 0).unwrap())
+    );
+}
+
+#[test]
+fn test_business_day_schedule_skips_holidays() {
+    let date = |m, d| NaiveDate::from_ymd_opt(2023, m, d).unwrap();
+    let half_eight = NaiveTime::from_hms_opt(8, 30, 0).unwrap();
+    let at = |m, d| date(m, d).and_time(half_eight).and_utc();
+
+    // Friday 22 December, then Christmas and Boxing Day off
+    let schedule =
+        BusinessDaySchedule::new(half_eight).with_holidays(vec![date(12, 25), date(12, 26)]);
+    assert_eq!(schedule.describe(), "every business day at 08:30");
+    assert_eq!(
+        schedule.iter_from(at(12, 22)).take(3).collect::<Vec<_>>(),
+        vec![at(12, 22), at(12, 27), at(12, 28)]
+    );
+
+    // A user-defined calendar
+    struct NoFirsts;
+    impl HolidayCalendar for NoFirsts {
+        fn is_holiday(&self, date: NaiveDate) -> bool {
+            date.day() == 1
+        }
+    }
+    let schedule = BusinessDaySchedule::new(half_eight).with_holidays(NoFirsts);
+    assert_eq!(schedule.next_occurrence(at(1, 31)), Some(at(2, 2)));
+}
+
+#[test]
+fn test_business_day_schedules_share_calendar() {
+    let date = |m, d| NaiveDate::from_ymd_opt(2023, m, d).unwrap();
+    let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    let five = NaiveTime::from_hms_opt(17, 0, 0).unwrap();
+
+    // Monday 1 May is a bank holiday
+    let calendar = Arc::new(Calendar::new("UK").with_holidays([date(5, 1)]));
+    let opening = BusinessDaySchedule::new(nine).with_calendar(Arc::clone(&calendar));
+    let closing = BusinessDaySchedule::new(five).with_calendar(Arc::clone(&calendar));
+    assert_eq!(
+        opening.describe(),
+        "every business day on the UK calendar at 09:00"
+    );
+
+    let friday = date(4, 28).and_time(nine).and_utc();
+    assert_eq!(
+        opening.next_occurrence(friday),
+        Some(date(5, 2).and_time(nine).and_utc())
+    );
+    assert_eq!(
+        closing.next_occurrence(friday + chrono::TimeDelta::hours(8)),
+        Some(date(5, 2).and_time(five).and_utc())
+    );
+
+    // Other weekends come from the calendar too
+    let gulf = Calendar::new("Gulf").with_weekend([Weekday::Fri, Weekday::Sat]);
+    assert!(gulf.is_business_day(date(4, 30)));
+    assert!(!gulf.is_business_day(date(4, 28)));
+}
+
+#[test]
+fn test_trading_schedule() {
+    let date = |m, d| NaiveDate::from_ymd_opt(2023, m, d).unwrap();
+    let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+    let at = |m, d, hour, minute| date(m, d).and_time(time(hour, minute)).and_utc();
+
+    // Independence Day off, with a half day before it
+    let calendar = Arc::new(
+        Calendar::new("NYSE")
+            .with_holidays([date(7, 4)])
+            .with_early_close(date(7, 3), time(13, 0)),
+    );
+    let session = || TradingSchedule::new(Arc::clone(&calendar), time(9, 30), time(16, 0)).unwrap();
+
+    let after_open = session().after_open(Duration::from_secs(5 * 60));
+    assert_eq!(
+        after_open.describe(),
+        "5m after the open on the NYSE calendar"
+    );
+    assert_eq!(
+        after_open
+            .iter_from(at(6, 30, 12, 0))
+            .take(3)
+            .collect::<Vec<_>>(),
+        vec![at(7, 3, 9, 35), at(7, 5, 9, 35), at(7, 6, 9, 35)]
+    );
+
+    // The half day closes at 13:00, so the close-relative fire comes early
+    let before_close = session().before_close(Duration::from_secs(10 * 60));
+    assert_eq!(
+        before_close
+            .iter_from(at(7, 3, 0, 0))
+            .take(2)
+            .collect::<Vec<_>>(),
+        vec![at(7, 3, 12, 50), at(7, 5, 15, 50)]
+    );
+    // And an offset past the early close has no fire that day
+    let afternoon = session().after_open(Duration::from_secs(4 * 3600));
+    assert_eq!(
+        afternoon.next_occurrence(at(7, 1, 0, 0)),
+        Some(at(7, 5, 13, 30))
+    );
+
+    assert!(matches!(
+        TradingSchedule::new(Arc::clone(&calendar), time(16, 0), time(9, 30)),
+        Err(SchedulerError::InvalidConfiguration)
+    ));
+}
+
+#[test]
+fn test_cron_on_weekday_set() {
+    let schedule = CronSchedule::new()
+        .minute(0)
+        .unwrap()
+        .hour(9)
+        .unwrap()
+        .on_weekdays([Weekday::Mon, Weekday::Wed, Weekday::Fri])
+        .unwrap();
+    assert_eq!(schedule.to_cron_expression().unwrap(), "0 9 * * 1,3,5");
+
+    let monday = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+    let days: Vec<Weekday> = schedule
+        .iter_from(monday)
+        .take(4)
+        .map(|fire| fire.weekday())
+        .collect();
+    assert_eq!(
+        days,
+        vec![Weekday::Mon, Weekday::Wed, Weekday::Fri, Weekday::Mon]
+    );
+    assert!(CronSchedule::new().on_weekdays([]).is_err());
+}
+
+#[test]
+fn test_cron_weekday_mornings() {
+    let schedule = CronSchedule::weekday_mornings(7, 30).unwrap();
+
+    // Friday the 6th, so the weekend comes straight after the first fire
+    let friday = Utc.with_ymd_and_hms(2023, 1, 6, 0, 0, 0).unwrap();
+    let fires: Vec<DateTime<Utc>> = schedule.iter_from(friday).take(6).collect();
+    let days: Vec<Weekday> = fires.iter().map(|fire| fire.weekday()).collect();
+    assert_eq!(
+        days,
+        vec![
+            Weekday::Fri,
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri
+        ]
+    );
+    assert!(fires
+        .iter()
+        .all(|fire| (fire.hour(), fire.minute(), fire.second()) == (7, 30, 0)));
+    assert!(CronSchedule::weekday_mornings(24, 0).is_err());
+}
+
+#[test]
+fn test_monthly_schedule_clamps_short_months() {
+    let date = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 9, 0, 0).unwrap();
+    let start = date(2024, 1, 1);
+
+    // The 31st, every month, lands on the last day of shorter months
+    let month_end = MonthlySchedule::every(1)
+        .unwrap()
+        .on_day(31)
+        .unwrap()
+        .at(9, 0)
+        .unwrap()
+        .starting(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+    assert_eq!(
+        month_end.iter_from(start).take(4).collect::<Vec<_>>(),
+        vec![
+            date(2024, 1, 31),
+            date(2024, 2, 29),
+            date(2024, 3, 31),
+            date(2024, 4, 30)
+        ]
+    );
+
+    // Every three months from February, skipping months without a 31st
+    let quarterly = MonthlySchedule::every(3)
+        .unwrap()
+        .on_day(31)
+        .unwrap()
+        .at(9, 0)
+        .unwrap()
+        .starting(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap())
+        .with_short_month_rule(ShortMonthRule::Skip);
+    assert_eq!(
+        quarterly.describe(),
+        "every 3 months on day 31 at 09:00 (skipping short months)"
+    );
+    assert_eq!(
+        quarterly.iter_from(start).take(3).collect::<Vec<_>>(),
+        vec![date(2024, 5, 31), date(2024, 8, 31), date(2025, 5, 31)]
+    );
+
+    assert!(MonthlySchedule::every(0).is_err());
+    assert!(MonthlySchedule::every(1).unwrap().on_day(32).is_err());
+}
+
+#[test]
+fn test_weekly_schedule_every_other_week() {
+    let at = |m, d| Utc.with_ymd_and_hms(2023, m, d, 10, 0, 0).unwrap();
+    let biweekly = WeeklySchedule::every(2)
+        .unwrap()
+        .on(Weekday::Tue)
+        .at(10, 0)
+        .unwrap()
+        .starting(NaiveDate::from_ymd_opt(2023, 1, 3).unwrap());
+    assert_eq!(biweekly.describe(), "every 2 weeks on Tue at 10:00");
+
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        biweekly.iter_from(start).take(3).collect::<Vec<_>>(),
+        vec![at(1, 3), at(1, 17), at(1, 31)]
+    );
+    // Off weeks are skipped even when polling from inside them
+    assert_eq!(biweekly.next_occurrence(at(1, 9)), Some(at(1, 17)));
+
+    // Several weekdays in the same on week, never before the anchor date
+    let twice = WeeklySchedule::every(2)
+        .unwrap()
+        .on(Weekday::Thu)
+        .on(Weekday::Mon)
+        .at(10, 0)
+        .unwrap()
+        .starting(NaiveDate::from_ymd_opt(2023, 1, 4).unwrap());
+    assert_eq!(
+        twice.iter_from(start).take(3).collect::<Vec<_>>(),
+        vec![at(1, 5), at(1, 16), at(1, 19)]
+    );
+    assert!(WeeklySchedule::every(0).is_err());
+}
+
+#[test]
+fn test_calendar_quarter_schedules() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let at = |m, d| Utc.with_ymd_and_hms(2023, m, d, 6, 0, 0).unwrap();
+
+    let first_day = FiscalSchedule::calendar_quarters().at(6, 0).unwrap();
+    assert_eq!(
+        first_day.iter_from(start).take(4).collect::<Vec<_>>(),
+        vec![at(1, 1), at(4, 1), at(7, 1), at(10, 1)]
+    );
+
+    // 1 April 2023 is a Saturday, and 1 July a Saturday too
+    let third_business_day = FiscalSchedule::calendar_quarters()
+        .business_day_of_quarter(3)
+        .unwrap()
+        .at(6, 0)
+        .unwrap();
+    assert_eq!(
+        third_business_day.describe(),
+        "fiscal every quarter business day 3 at 06:00 (year starting month 1)"
+    );
+    assert_eq!(
+        third_business_day
+            .iter_from(start)
+            .take(3)
+            .collect::<Vec<_>>(),
+        vec![at(1, 4), at(4, 5), at(7, 5)]
+    );
+    assert!(FiscalSchedule::calendar_quarters()
+        .business_day_of_quarter(67)
+        .is_err());
+}
+
+#[test]
+fn test_random_window_schedule() {
+    let schedule = RandomWindowSchedule::daily(9..10).unwrap().with_seed(7);
+    assert_eq!(
+        schedule.describe(),
+        "daily at a random time between 09:00 and 10:00"
+    );
+
+    // One fire a day, always inside the window
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let fires: Vec<_> = schedule.iter_from(start).take(30).collect();
+    for (day, fire) in fires.iter().enumerate() {
+        assert_eq!(
+            fire.date_naive(),
+            (start + chrono::TimeDelta::days(day as i64)).date_naive()
+        );
+        assert_eq!(fire.hour(), 9);
+    }
+    assert!(fires
+        .windows(2)
+        .any(|pair| pair[0].time() != pair[1].time()));
+
+    // The same seed picks the same instants, and polling again doesn't move the day's pick
+    let again = RandomWindowSchedule::daily(9..10).unwrap().with_seed(7);
+    assert_eq!(again.next_occurrence(start), Some(fires[0]));
+    assert_eq!(again.next_occurrence(start), Some(fires[0]));
+
+    assert!(RandomWindowSchedule::daily(9..9).is_err());
+    assert!(RandomWindowSchedule::daily(20..25).is_err());
+}
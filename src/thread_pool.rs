@@ -0,0 +1,76 @@
+use super::*;
+
+enum Message {
+    Run(Box<dyn FnOnce() + Send>),
+    Shutdown,
+}
+
+// Fixed set of worker threads running scheduled tasks off the scheduler thread
+pub struct ThreadPoolExecutor {
+    sender: mpsc::Sender<Message>,
+    workers: Vec<thread::JoinHandle<()>>,
+}
+
+impl ThreadPoolExecutor {
+    pub fn new(workers: usize) -> Result<Self, SchedulerError> {
+        if workers == 0 {
+            return Err(SchedulerError::InvalidConfiguration);
+        }
+
+        let (sender, receiver) = mpsc::channel::<Message>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = (0..workers)
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                thread::spawn(move || loop {
+                    let message = receiver
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .recv();
+                    match message {
+                        // A panicking task is reported by the panic hook but keeps the worker alive
+                        Ok(Message::Run(task)) => {
+                            let _ = catch_unwind(AssertUnwindSafe(task));
+                        }
+                        Ok(Message::Shutdown) | Err(_) => break,
+                    }
+                })
+            })
+            .collect();
+
+        Ok(Self { sender, workers })
+    }
+
+    pub fn execute(&self, task: impl FnOnce() + Send + 'static) {
+        // Workers only go away on drop, so the channel is always open here
+        let _ = self.sender.send(Message::Run(Box::new(task)));
+    }
+
+    // Task handing each run to the pool. Overlapping runs of the same task wait their turn
+    pub(crate) fn dispatcher<F: FnMut() + Send + 'static>(
+        &self,
+        task: F,
+    ) -> impl FnMut() + 'static {
+        let task = Arc::new(Mutex::new(task));
+        let sender = self.sender.clone();
+
+        // A run that panicked leaves the task poisoned, but the next run still goes ahead
+        move || {
+            let task = Arc::clone(&task);
+            let run = move || (task.lock().unwrap_or_else(PoisonError::into_inner))();
+            let _ = sender.send(Message::Run(Box::new(run)));
+        }
+    }
+}
+
+impl Drop for ThreadPoolExecutor {
+    // Queued runs finish before the workers shut down
+    fn drop(&mut self) {
+        for _ in &self.workers {
+            let _ = self.sender.send(Message::Shutdown);
+        }
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use super::*;
+
+// Compact duration formatting, eg: 1h30m, 45s
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (days, hours, minutes, seconds) = (
+        secs / 86400,
+        secs % 86400 / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+    );
+
+    let mut formatted = String::new();
+    for (value, unit) in [(days, "d"), (hours, "h"), (minutes, "m"), (seconds, "s")] {
+        if value > 0 {
+            formatted.push_str(&format!("{value}{unit}"));
+        }
+    }
+
+    if formatted.is_empty() {
+        formatted.push_str("0s");
+    }
+    formatted
+}
+
+// English ordinal for a count, eg: 1st, 2nd, 3rd, 11th
+pub(crate) fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+// Saturday or Sunday, which business day counts skip
+pub(crate) fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+// Validate a batch of schedules, returning the index and error of each failing one
+pub fn validate_all(schedules: &[Box<dyn Schedule>]) -> Vec<(usize, SchedulerError)> {
+    schedules
+        .iter()
+        .enumerate()
+        .filter_map(|(index, schedule)| schedule.validate().err().map(|error| (index, error)))
+        .collect()
+}
+
+// Fire times added and removed when replacing `old` with `new` within `window`
+pub fn schedule_diff(
+    old: &dyn Schedule,
+    new: &dyn Schedule,
+    window: Range<DateTime<Utc>>,
+) -> (Vec<DateTime<Utc>>, Vec<DateTime<Utc>>) {
+    let old_times = old.occurrences_between(window.start, window.end);
+    let new_times = new.occurrences_between(window.start, window.end);
+
+    let added = new_times
+        .iter()
+        .filter(|time| !old_times.contains(time))
+        .copied()
+        .collect();
+    let removed = old_times
+        .iter()
+        .filter(|time| !new_times.contains(time))
+        .copied()
+        .collect();
+
+    (added, removed)
+}
+
+// Whether two schedules agree on their next `samples` occurrences after `after`, eg: to spot
+// the same job registered twice under differently built schedules
+pub fn schedule_eq(
+    a: &dyn Schedule,
+    b: &dyn Schedule,
+    after: DateTime<Utc>,
+    samples: usize,
+) -> bool {
+    a.occurrences_limited(after, samples, DateTime::<Utc>::MAX_UTC)
+        == b.occurrences_limited(after, samples, DateTime::<Utc>::MAX_UTC)
+}
+
+// CronSchedule weekday number for a chrono weekday, counting from Monday = 0
+pub fn weekday_to_cron(weekday: Weekday) -> u32 {
+    weekday.num_days_from_monday()
+}
+
+// Chrono weekday for a CronSchedule weekday number, counting from Monday = 0
+pub fn cron_to_weekday(weekday: u32) -> Result<Weekday, SchedulerError> {
+    u8::try_from(weekday)
+        .ok()
+        .and_then(|weekday| Weekday::try_from(weekday).ok())
+        .ok_or(SchedulerError::InvalidConfiguration)
+}
+
+// Best-fit schedule for observed fire times: a daily, weekly or set-of-weekdays cron time, else a
+// fixed interval
+pub fn infer_schedule(times: &[DateTime<Utc>]) -> Option<Box<dyn Schedule>> {
+    if times.len() < 2 || times.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return None;
+    }
+    let gaps: Vec<chrono::TimeDelta> = times.windows(2).map(|pair| pair[1] - pair[0]).collect();
+
+    // Same wall-clock minute every time, on a day pattern the gaps bear out
+    let first = times[0];
+    let same_time_of_day = times.iter().all(|time| {
+        (time.hour(), time.minute(), time.second(), time.nanosecond())
+            == (first.hour(), first.minute(), 0, 0)
+    });
+    if same_time_of_day {
+        let cron = CronSchedule::new()
+            .hour(first.hour())
+            .ok()?
+            .minute(first.minute())
+            .ok()?;
+        if gaps.iter().all(|gap| *gap == chrono::TimeDelta::days(1)) {
+            return Some(Box::new(cron));
+        }
+        if gaps.iter().all(|gap| *gap == chrono::TimeDelta::weeks(1)) {
+            return Some(Box::new(
+                cron.weekday(first.weekday().num_days_from_monday()).ok()?,
+            ));
+        }
+
+        // Every day of a set of weekdays, seen over at least a week and skipping none of them
+        let weekdays: Vec<u32> = times
+            .iter()
+            .map(|time| time.weekday().num_days_from_monday())
+            .collect();
+        let skips_none = times.windows(2).all(|pair| {
+            (1..(pair[1] - pair[0]).num_days()).all(|offset| {
+                let skipped = pair[0] + chrono::TimeDelta::days(offset);
+                !weekdays.contains(&skipped.weekday().num_days_from_monday())
+            })
+        });
+        let spans_week = times[times.len() - 1] - first >= chrono::TimeDelta::weeks(1);
+        if skips_none && spans_week {
+            return Some(Box::new(cron.weekdays(weekdays).ok()?));
+        }
+    }
+
+    // Otherwise only an exactly regular spacing counts
+    if gaps.iter().all(|gap| *gap == gaps[0]) {
+        let interval = gaps[0].to_std().ok()?;
+        return IntervalSchedule::new(interval, first)
+            .ok()
+            .map(|schedule| Box::new(schedule) as Box<dyn Schedule>);
+    }
+
+    None
+}